@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::os::unix::prelude::*;
 use std::path::Path;
 use std::rc::Rc;
@@ -8,14 +9,17 @@ use input::event::{DeviceEvent, EventTrait};
 use input::{Libinput, LibinputInterface};
 use log::{debug, info, log_enabled};
 use nix::poll::{PollFd, PollFlags, PollTimeout, poll};
+use nix::sys::signal::{SigSet, Signal};
+use nix::sys::signalfd::SignalFd;
+use nix::time::{ClockId, clock_gettime};
 
-use crate::config::Config;
+use crate::config::{self, Config};
 use crate::default_libinput_interface::DefaultLibinputInterface;
 use crate::device_fd::{DeviceFd, DeviceFdMap};
 use crate::errors::Error;
 use crate::inspect_event::inspect_event;
 use crate::sink_device::SinkDevice;
-use crate::sink_event::SinkEvent;
+use crate::sink_event::{DeviceState, SinkEvent};
 
 type DeviceFdMapPtr = Rc<RefCell<DeviceFdMap>>;
 
@@ -23,6 +27,12 @@ pub struct App<'a> {
     config: &'a Config,
     device_fd_map: DeviceFdMapPtr,
     sink_device: SinkDevice,
+    grabbed_devices: HashMap<String, GrabbedDevice<'a>>,
+}
+
+struct GrabbedDevice<'a> {
+    config: &'a config::Device,
+    state: DeviceState,
 }
 
 impl<'a> App<'a> {
@@ -32,6 +42,7 @@ impl<'a> App<'a> {
             config,
             device_fd_map,
             sink_device,
+            grabbed_devices: HashMap::new(),
         }
     }
 
@@ -42,14 +53,71 @@ impl<'a> App<'a> {
             .udev_assign_seat("seat0")
             .expect("failed to assign seat");
 
+        let mut signals = SigSet::empty();
+        signals.add(Signal::SIGINT);
+        signals.add(Signal::SIGTERM);
+        signals.thread_block()?;
+        let signal_fd = SignalFd::new(&signals)?;
+
+        let res = self.poll_loop(&mut libinput, &signal_fd);
+
+        for (sysname, grabbed) in &self.grabbed_devices {
+            log_device_stats(sysname, &grabbed.state);
+        }
+        // Do not leave synthetic held buttons such as drag lock pressed, even if the loop failed.
+        let released = self.sink_device.release_all();
+        res.and(released)
+    }
+
+    fn poll_loop(&mut self, libinput: &mut Libinput, signal_fd: &SignalFd) -> Result<(), Error> {
         let libinput_for_poll = libinput.clone();
-        let mut poll_fds = [PollFd::new(libinput_for_poll.as_fd(), PollFlags::POLLIN)];
+        let mut poll_fds = [
+            PollFd::new(libinput_for_poll.as_fd(), PollFlags::POLLIN),
+            PollFd::new(signal_fd.as_fd(), PollFlags::POLLIN),
+        ];
+
+        while poll(&mut poll_fds, self.poll_timeout()?)? > -1 {
+            if poll_fds[1]
+                .revents()
+                .is_some_and(|x| x.contains(PollFlags::POLLIN))
+            {
+                if let Some(x) = signal_fd.read_signal()? {
+                    info!("received signal {}, shutting down", x.ssi_signo);
+                }
+                break;
+            }
 
-        while poll(&mut poll_fds, PollTimeout::NONE)? > -1 {
             libinput.dispatch()?;
-            for event in &mut libinput {
+            for event in &mut *libinput {
                 self.handle_event(&event)?;
             }
+            self.handle_timers()?;
+        }
+        Ok(())
+    }
+
+    fn poll_timeout(&self) -> Result<PollTimeout, Error> {
+        let deadline = match self
+            .grabbed_devices
+            .values()
+            .filter_map(|x| x.state.deadline())
+            .min()
+        {
+            Some(x) => x,
+            None => return Ok(PollTimeout::NONE),
+        };
+        // Round up so as not to wake up before the deadline.
+        let timeout_ms = deadline.saturating_sub(now_usec()?).div_ceil(1000);
+        Ok(PollTimeout::try_from(timeout_ms).unwrap_or(PollTimeout::MAX))
+    }
+
+    fn handle_timers(&mut self) -> Result<(), Error> {
+        let now = now_usec()?;
+        for (sysname, grabbed) in self.grabbed_devices.iter_mut() {
+            if grabbed.state.deadline().is_some_and(|x| x <= now) {
+                let sink_event = SinkEvent::from_timer(now, grabbed.config, &mut grabbed.state);
+                self.sink_device.send_event(sysname, &sink_event)?;
+            }
         }
         Ok(())
     }
@@ -93,10 +161,36 @@ impl<'a> App<'a> {
                     ))
                 })?;
                 device_fd.grab()?;
+                self.grabbed_devices.insert(
+                    device.sysname().to_string(),
+                    GrabbedDevice {
+                        config: device_config,
+                        state: DeviceState::default(),
+                    },
+                );
+            }
+            Event::Device(DeviceEvent::Removed(_)) => {
+                info!(
+                    "release removed device: {} ({})",
+                    device.sysname(),
+                    device.name()
+                );
+                if let Some(grabbed) = self.grabbed_devices.remove(device.sysname()) {
+                    log_device_stats(device.sysname(), &grabbed.state);
+                }
+                self.sink_device.release(device.sysname())?;
             }
             Event::Pointer(ev) => {
-                let sink_event = SinkEvent::from_pointer_event(ev, device_config)?;
-                self.sink_device.send_event(&sink_event)?;
+                let grabbed = self
+                    .grabbed_devices
+                    .entry(device.sysname().to_string())
+                    .or_insert_with(|| GrabbedDevice {
+                        config: device_config,
+                        state: DeviceState::default(),
+                    });
+                let sink_event =
+                    SinkEvent::from_pointer_event(ev, grabbed.config, &mut grabbed.state)?;
+                self.sink_device.send_event(device.sysname(), &sink_event)?;
             }
             _ => return Err(Error::Message(format!("unexpected event: {:?}", event))),
         }
@@ -104,6 +198,19 @@ impl<'a> App<'a> {
     }
 }
 
+fn log_device_stats(sysname: &str, state: &DeviceState) {
+    let suppressed = state.debounce_suppressed();
+    if suppressed > 0 {
+        info!("{}: suppressed {} button bounces", sysname, suppressed);
+    }
+}
+
+fn now_usec() -> Result<u64, Error> {
+    // libinput event times are based on CLOCK_MONOTONIC.
+    let ts = clock_gettime(ClockId::CLOCK_MONOTONIC)?;
+    Ok(ts.tv_sec() as u64 * 1_000_000 + ts.tv_nsec() as u64 / 1_000)
+}
+
 struct AppLibinputInterface {
     iface: DefaultLibinputInterface,
     device_fd_map: DeviceFdMapPtr,
@@ -1,9 +1,27 @@
+mod accel_curve;
 mod accel_profile;
+mod autoscroll;
+mod axis_lock;
 mod button;
+mod button_target;
+mod chord;
+mod dead_zone;
+mod debounce;
 mod device;
 mod device_info;
+mod factor;
+mod key;
+mod kinetic_scroll;
+mod layer;
 mod match_rule;
+mod motion_factor;
+mod ratchet;
+mod repeat;
+mod scroll_acceleration;
+mod scroll_direction;
 mod scroll_factor;
+mod wheel_mapping;
+mod wheel_output;
 
 use std::path::Path;
 
@@ -11,13 +29,22 @@ use serde::Deserialize;
 
 use crate::errors::Error;
 
-pub use self::button::Button;
+pub use self::accel_curve::AccelCurve;
+pub use self::accel_profile::AccelProfile;
+pub use self::button::{BUTTON_CODES, Button};
+pub use self::button_target::ButtonTarget;
 pub use self::device::Device;
 use self::device_info::DeviceInfo;
+pub use self::key::{Key, is_keyboard_key};
+pub use self::layer::{Layer, LayerMode};
+pub use self::scroll_acceleration::ScrollAcceleration;
+pub use self::scroll_direction::ScrollDirection;
 pub use self::scroll_factor::ScrollFactorPair;
+pub use self::wheel_output::WheelOutput;
 
 #[derive(Debug, Default, Deserialize)]
 pub struct Config {
+    #[serde(deserialize_with = "device::deserialize_devices")]
     pub devices: Vec<Device>,
 }
 
@@ -31,6 +58,22 @@ impl Config {
     pub fn matched_device(&self, device_info: &DeviceInfo) -> Option<&Device> {
         self.devices.iter().find(|x| x.matches(device_info))
     }
+
+    /// Returns the wheel output which covers all devices.
+    pub fn wheel_output(&self) -> WheelOutput {
+        self.devices
+            .iter()
+            .map(|x| x.wheel_output)
+            .reduce(WheelOutput::union)
+            .unwrap_or_default()
+    }
+
+    /// Returns true if some device can emit keyboard keys.
+    pub fn has_keyboard_keys(&self) -> bool {
+        self.devices.iter().any(|x| {
+            x.zoom_modifier.is_some() || x.wheel_mapping.iter().any(|x| x.target.is_keyboard_key())
+        })
+    }
 }
 
 #[cfg(test)]
@@ -57,4 +100,78 @@ mod tests {
         };
         assert!(config.matched_device(&device_info).is_some());
     }
+
+    #[test]
+    fn test_wheel_output() {
+        let config = Config { devices: vec![] };
+        assert_eq!(config.wheel_output(), WheelOutput::Both);
+
+        let config: Config = serde_yaml::from_str(
+            r#"
+            devices:
+              - match_rule: {name: moo}
+                wheel_output: hires
+              - match_rule: {name: woof}
+                wheel_output: hires
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.wheel_output(), WheelOutput::Hires);
+    }
+
+    #[test]
+    fn test_has_keyboard_keys() {
+        let config: Config = serde_yaml::from_str(
+            r#"
+            devices:
+              - match_rule: {name: moo}
+                wheel_mapping:
+                  - {direction: left, target: BTN_SIDE}
+            "#,
+        )
+        .unwrap();
+        assert!(!config.has_keyboard_keys());
+
+        let config: Config = serde_yaml::from_str(
+            r#"
+            devices:
+              - match_rule: {name: moo}
+                wheel_mapping:
+                  - {direction: up, target: KEY_VOLUMEUP}
+            "#,
+        )
+        .unwrap();
+        assert!(config.has_keyboard_keys());
+    }
+
+    #[test]
+    fn test_shared_modifier() {
+        let config = serde_yaml::from_str::<Config>(
+            r#"
+            devices:
+              - match_rule: {name: moo}
+                horizontal_scroll_modifier: BTN_SIDE
+                wheel_mapping:
+                  - {direction: up, target: KEY_VOLUMEUP, modifier: BTN_SIDE}
+                  - {direction: down, target: KEY_VOLUMEDOWN, modifier: BTN_SIDE}
+            "#,
+        );
+        assert!(config.unwrap_err().to_string().starts_with(
+            "horizontal_scroll_modifier and wheel_mapping modifier have the same button"
+        ));
+
+        // Wheel mappings can share their modifier.
+        let config = serde_yaml::from_str::<Config>(
+            r#"
+            devices:
+              - match_rule: {name: moo}
+                zoom_modifier: BTN_EXTRA
+                horizontal_scroll_modifier: BTN_SIDE
+                wheel_mapping:
+                  - {direction: up, target: KEY_VOLUMEUP, modifier: BTN_FORWARD}
+                  - {direction: down, target: KEY_VOLUMEDOWN, modifier: BTN_FORWARD}
+            "#,
+        );
+        assert!(config.is_ok());
+    }
 }
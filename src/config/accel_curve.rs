@@ -0,0 +1,86 @@
+use serde::Deserialize;
+
+/// Maximum number of points, as in libinput.
+const MAX_POINTS: usize = 64;
+
+/// Output speed as a function of the input speed, given by points at a fixed step of the input speed.
+///
+/// Speeds are in units per millisecond of the unaccelerated motion. The points are interpolated linearly, and extrapolated from the last two points beyond the end.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "RawAccelCurve")]
+pub struct AccelCurve {
+    points: Vec<f64>,
+    step: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawAccelCurve {
+    points: Vec<f64>,
+    step: f64,
+}
+
+impl TryFrom<RawAccelCurve> for AccelCurve {
+    type Error = TryFromRawAccelCurveError;
+
+    fn try_from(value: RawAccelCurve) -> Result<Self, Self::Error> {
+        let is_valid_step = value.step > 0.0 && value.step.is_finite();
+        let is_valid_points = (2..=MAX_POINTS).contains(&value.points.len())
+            && value.points.iter().all(|x| *x >= 0.0 && x.is_finite());
+        if !is_valid_step || !is_valid_points {
+            return Err(TryFromRawAccelCurveError());
+        }
+        Ok(AccelCurve {
+            points: value.points,
+            step: value.step,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error(
+    "step must be a positive finite number, and points must be 2 to 64 non-negative finite numbers"
+)]
+pub struct TryFromRawAccelCurveError();
+
+impl AccelCurve {
+    pub fn speed(&self, speed: f64) -> f64 {
+        let position = speed / self.step;
+        let i = (position.floor() as usize).min(self.points.len() - 2);
+        let (y0, y1) = (self.points[i], self.points[i + 1]);
+        y0 + (y1 - y0) * (position - i as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de() {
+        assert_eq!(
+            serde_yaml::from_str::<AccelCurve>("{points: [0, 1, 3], step: 2}").unwrap(),
+            AccelCurve {
+                points: vec![0.0, 1.0, 3.0],
+                step: 2.0,
+            }
+        );
+        assert!(serde_yaml::from_str::<AccelCurve>("{points: [0, 1], step: 0}").is_err());
+        assert!(serde_yaml::from_str::<AccelCurve>("{points: [1], step: 1}").is_err());
+        assert!(serde_yaml::from_str::<AccelCurve>("{points: [0, -1], step: 1}").is_err());
+    }
+
+    #[test]
+    fn test_speed() {
+        let curve = AccelCurve {
+            points: vec![0.0, 1.0, 3.0],
+            step: 2.0,
+        };
+        assert_eq!(curve.speed(0.0), 0.0);
+        assert_eq!(curve.speed(1.0), 0.5);
+        assert_eq!(curve.speed(3.0), 2.0);
+        assert_eq!(curve.speed(4.0), 3.0);
+        // Extrapolated from the last two points
+        assert_eq!(curve.speed(6.0), 5.0);
+    }
+}
@@ -1,17 +1,165 @@
-use serde::Deserialize;
+use std::fmt;
 
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use super::accel_curve::AccelCurve;
+
+#[derive(Debug, PartialEq)]
 pub enum AccelProfile {
     Adaptive,
     Flat,
+    /// Acceleration curves which ponde applies itself to the unaccelerated motion, as the libinput bindings lack the custom profile.
+    Custom(CustomAccel),
+}
+
+/// Acceleration curves by the type of motion. The fallback curve is used if there is no curve for the type.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "RawCustomAccel")]
+pub struct CustomAccel {
+    motion: Option<AccelCurve>,
+    fallback: Option<AccelCurve>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCustomAccel {
+    motion: Option<AccelCurve>,
+    fallback: Option<AccelCurve>,
+}
+
+impl TryFrom<RawCustomAccel> for CustomAccel {
+    type Error = TryFromRawCustomAccelError;
+
+    fn try_from(value: RawCustomAccel) -> Result<Self, Self::Error> {
+        if value.motion.is_none() && value.fallback.is_none() {
+            return Err(TryFromRawCustomAccelError());
+        }
+        Ok(CustomAccel {
+            motion: value.motion,
+            fallback: value.fallback,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("custom acceleration must have a motion or fallback curve")]
+pub struct TryFromRawCustomAccelError();
+
+impl CustomAccel {
+    /// Returns the curve for pointer motion.
+    pub fn motion(&self) -> &AccelCurve {
+        self.motion
+            .as_ref()
+            .or(self.fallback.as_ref())
+            .expect("custom acceleration has a curve")
+    }
 }
 
-impl From<AccelProfile> for input::AccelProfile {
-    fn from(x: AccelProfile) -> Self {
-        match x {
-            AccelProfile::Adaptive => input::AccelProfile::Adaptive,
-            AccelProfile::Flat => input::AccelProfile::Flat,
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCustom {
+    custom: CustomAccel,
+}
+
+impl AccelProfile {
+    /// Returns the profile to set to libinput, which is none for the custom profile.
+    pub fn libinput_profile(&self) -> Option<input::AccelProfile> {
+        match self {
+            AccelProfile::Adaptive => Some(input::AccelProfile::Adaptive),
+            AccelProfile::Flat => Some(input::AccelProfile::Flat),
+            AccelProfile::Custom(_) => None,
         }
     }
 }
+
+impl<'de> Deserialize<'de> for AccelProfile {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(AccelProfileVisitor)
+    }
+}
+
+struct AccelProfileVisitor;
+
+impl<'de> Visitor<'de> for AccelProfileVisitor {
+    type Value = AccelProfile;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("adaptive, flat or custom curves")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        match v {
+            "adaptive" => Ok(AccelProfile::Adaptive),
+            "flat" => Ok(AccelProfile::Flat),
+            "custom" => Err(E::custom(
+                "`custom` requires a `motion` or `fallback` curve of `points` and `step`",
+            )),
+            _ => Err(E::unknown_variant(v, &["adaptive", "flat", "custom"])),
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        let raw = RawCustom::deserialize(MapAccessDeserializer::new(map))?;
+        Ok(AccelProfile::Custom(raw.custom))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de() {
+        assert_eq!(
+            serde_yaml::from_str::<AccelProfile>("flat").unwrap(),
+            AccelProfile::Flat
+        );
+        assert!(serde_yaml::from_str::<AccelProfile>("linear").is_err());
+
+        let profile = serde_yaml::from_str::<AccelProfile>(
+            r#"
+            custom:
+              fallback:
+                points: [0, 1, 3]
+                step: 2
+            "#,
+        )
+        .unwrap();
+        let expected = serde_yaml::from_str::<AccelCurve>("{points: [0, 1, 3], step: 2}").unwrap();
+        match profile {
+            AccelProfile::Custom(x) => assert_eq!(x.motion(), &expected),
+            _ => panic!("unexpected profile {:?}", profile),
+        }
+
+        let profile = serde_yaml::from_str::<AccelProfile>(
+            r#"
+            custom:
+              motion:
+                points: [0, 2]
+                step: 1
+              fallback:
+                points: [0, 1, 3]
+                step: 2
+            "#,
+        )
+        .unwrap();
+        let expected = serde_yaml::from_str::<AccelCurve>("{points: [0, 2], step: 1}").unwrap();
+        match profile {
+            AccelProfile::Custom(x) => assert_eq!(x.motion(), &expected),
+            _ => panic!("unexpected profile {:?}", profile),
+        }
+
+        assert!(serde_yaml::from_str::<AccelProfile>("custom: {}").is_err());
+        assert_eq!(
+            serde_yaml::from_str::<AccelProfile>("custom")
+                .unwrap_err()
+                .to_string(),
+            "`custom` requires a `motion` or `fallback` curve of `points` and `step`"
+        );
+    }
+}
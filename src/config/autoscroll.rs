@@ -0,0 +1,78 @@
+use serde::Deserialize;
+
+use super::button::Button;
+
+const DEFAULT_SPEED: f64 = 0.1;
+const DEFAULT_DEAD_ZONE: f64 = 10.0;
+
+/// Scrolls by the distance of the pointer from where autoscroll started.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "RawAutoscroll")]
+pub struct Autoscroll {
+    /// Button whose click starts autoscroll.
+    pub button: Button,
+    /// Scroll speed in notches per second per unit of distance beyond the dead zone.
+    pub speed: f64,
+    /// Distance from the anchor within which it does not scroll.
+    pub dead_zone: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawAutoscroll {
+    button: Button,
+    #[serde(default = "default_speed")]
+    speed: f64,
+    #[serde(default = "default_dead_zone")]
+    dead_zone: f64,
+}
+
+fn default_speed() -> f64 {
+    DEFAULT_SPEED
+}
+
+fn default_dead_zone() -> f64 {
+    DEFAULT_DEAD_ZONE
+}
+
+impl TryFrom<RawAutoscroll> for Autoscroll {
+    type Error = TryFromRawAutoscrollError;
+
+    fn try_from(value: RawAutoscroll) -> Result<Self, Self::Error> {
+        let is_valid_speed = value.speed > 0.0 && value.speed.is_finite();
+        let is_valid_dead_zone = value.dead_zone >= 0.0 && value.dead_zone.is_finite();
+        if !is_valid_speed || !is_valid_dead_zone {
+            return Err(TryFromRawAutoscrollError());
+        }
+        Ok(Autoscroll {
+            button: value.button,
+            speed: value.speed,
+            dead_zone: value.dead_zone,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("speed must be a positive finite number, and dead_zone must be non-negative")]
+pub struct TryFromRawAutoscrollError();
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+
+    #[test]
+    fn test_de() {
+        assert_eq!(
+            serde_yaml::from_str::<Autoscroll>("button: BTN_MIDDLE").unwrap(),
+            Autoscroll {
+                button: Button::from_code(KeyCode::BTN_MIDDLE.code()),
+                speed: 0.1,
+                dead_zone: 10.0,
+            }
+        );
+        assert!(serde_yaml::from_str::<Autoscroll>("{button: BTN_MIDDLE, speed: 0}").is_err());
+        assert!(serde_yaml::from_str::<Autoscroll>("{button: BTN_MIDDLE, dead_zone: -1}").is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+
+const DEFAULT_DISTANCE: f64 = 60.0;
+const DEFAULT_DIAGONAL_RATIO: f64 = 0.5;
+
+/// Locks motion scrolling to its dominant axis.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "RawAxisLock")]
+pub struct AxisLock {
+    /// Scroll distance in hi-res units (120 per notch) from which the dominant axis is picked.
+    pub distance: f64,
+    /// Ratio of the minor axis to the dominant axis from which both axes are scrolled.
+    pub diagonal_ratio: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawAxisLock {
+    #[serde(default = "default_distance")]
+    distance: f64,
+    #[serde(default = "default_diagonal_ratio")]
+    diagonal_ratio: f64,
+}
+
+fn default_distance() -> f64 {
+    DEFAULT_DISTANCE
+}
+
+fn default_diagonal_ratio() -> f64 {
+    DEFAULT_DIAGONAL_RATIO
+}
+
+impl TryFrom<RawAxisLock> for AxisLock {
+    type Error = TryFromRawAxisLockError;
+
+    fn try_from(value: RawAxisLock) -> Result<Self, Self::Error> {
+        let is_valid_distance = value.distance >= 0.0 && value.distance.is_finite();
+        let is_valid_ratio = value.diagonal_ratio > 0.0 && value.diagonal_ratio.is_finite();
+        if !is_valid_distance || !is_valid_ratio {
+            return Err(TryFromRawAxisLockError());
+        }
+        Ok(AxisLock {
+            distance: value.distance,
+            diagonal_ratio: value.diagonal_ratio,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("distance must be a non-negative finite number, and diagonal_ratio must be positive")]
+pub struct TryFromRawAxisLockError();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de() {
+        assert_eq!(
+            serde_yaml::from_str::<AxisLock>("{}").unwrap(),
+            AxisLock {
+                distance: 60.0,
+                diagonal_ratio: 0.5,
+            }
+        );
+        assert_eq!(
+            serde_yaml::from_str::<AxisLock>("{distance: 0, diagonal_ratio: 2}").unwrap(),
+            AxisLock {
+                distance: 0.0,
+                diagonal_ratio: 2.0,
+            }
+        );
+        assert!(serde_yaml::from_str::<AxisLock>("distance: -1").is_err());
+        assert!(serde_yaml::from_str::<AxisLock>("diagonal_ratio: 0").is_err());
+    }
+}
@@ -1,22 +1,98 @@
+use std::fmt;
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 
 use evdev::KeyCode;
+use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer};
 
+/// Friendly names of common buttons.
+const ALIASES: &[(&str, KeyCode)] = &[
+    ("left", KeyCode::BTN_LEFT),
+    ("right", KeyCode::BTN_RIGHT),
+    ("middle", KeyCode::BTN_MIDDLE),
+    ("back", KeyCode::BTN_SIDE),
+    ("forward", KeyCode::BTN_EXTRA),
+];
+
+/// Codes of the buttons which the sink device advertises.
+pub const BUTTON_CODES: RangeInclusive<u16> = KeyCode::BTN_0.code()..=KeyCode::BTN_THUMBR.code();
+
+/// Maximum edit distance of the names suggested for a near miss.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+const MAX_SUGGESTIONS: usize = 3;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Button(KeyCode);
 
+impl FromStr for Button {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((_, code)) = ALIASES.iter().find(|(name, _)| *name == s) {
+            return Ok(Self(*code));
+        }
+        if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            return u16::from_str_radix(hex, 16)
+                .ok()
+                .filter(|x| is_button_code(*x))
+                .map(Self::from_code)
+                .ok_or_else(|| format!("unexpected button value {}", s));
+        }
+        if let Ok(number) = s.parse::<u64>() {
+            return Self::from_number(number);
+        }
+
+        KeyCode::from_str(s)
+            .ok()
+            .filter(|x| s.starts_with("BTN_") && is_button_code(x.code()))
+            .map(Self)
+            .ok_or_else(|| {
+                let suggestions = suggest(s);
+                if suggestions.is_empty() {
+                    format!("unexpected button value {}", s)
+                } else {
+                    format!(
+                        "unexpected button value {}, did you mean {}?",
+                        s,
+                        suggestions.join(", ")
+                    )
+                }
+            })
+    }
+}
+
 impl<'de> Deserialize<'de> for Button {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        KeyCode::from_str(&s)
-            .ok()
-            .filter(|_| s.starts_with("BTN_"))
-            .map(Self)
-            .ok_or_else(|| serde::de::Error::custom(format!("unexpected button value {}", s)))
+        deserializer.deserialize_any(ButtonVisitor)
+    }
+}
+
+struct ButtonVisitor;
+
+impl<'de> Visitor<'de> for ButtonVisitor {
+    type Value = Button;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a button name, alias, code or number")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Button::from_number(v).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        u64::try_from(v)
+            .map_err(|_| E::custom(format!("unexpected button value {}", v)))
+            .and_then(|x| self.visit_u64(x))
     }
 }
 
@@ -25,11 +101,71 @@ impl Button {
         Self(KeyCode::new(code))
     }
 
+    /// Converts a raw button code, or a libinput-style button number below `BTN_MISC`
+    /// (1 left, 2 middle, 3 right, 8 side, 9 extra and so on).
+    pub fn from_number(number: u64) -> Result<Self, String> {
+        let code = match u16::try_from(number) {
+            Ok(x) if x < KeyCode::BTN_0.code() => match x {
+                1 => Some(KeyCode::BTN_LEFT.code()),
+                2 => Some(KeyCode::BTN_MIDDLE.code()),
+                3 => Some(KeyCode::BTN_RIGHT.code()),
+                // 4 to 7 are wheel directions.
+                8.. => Some(KeyCode::BTN_SIDE.code() + x - 8)
+                    .filter(|x| *x < KeyCode::BTN_TRIGGER.code()),
+                _ => None,
+            },
+            Ok(x) => Some(x).filter(|x| is_button_code(*x)),
+            Err(_) => None,
+        };
+        code.map(Self::from_code)
+            .ok_or_else(|| format!("unexpected button value {}", number))
+    }
+
     pub fn code(&self) -> u16 {
         self.0.code()
     }
 }
 
+fn is_button_code(code: u16) -> bool {
+    BUTTON_CODES.contains(&code)
+}
+
+/// Returns the button names closest to `s`, which are within `MAX_SUGGESTION_DISTANCE`.
+fn suggest(s: &str) -> Vec<String> {
+    let s = s.to_ascii_uppercase();
+    let mut names = (0..=u16::MAX)
+        .filter(|x| is_button_code(*x))
+        .map(|x| format!("{:?}", KeyCode::new(x)))
+        .filter(|x| x.starts_with("BTN_"))
+        .map(|x| (edit_distance(&s, &x), x.len().abs_diff(s.len()), x))
+        .filter(|(distance, ..)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .collect::<Vec<_>>();
+    names.sort();
+    let closest = names.first().map(|(distance, ..)| *distance);
+    names
+        .into_iter()
+        .take_while(|(distance, ..)| Some(*distance) == closest)
+        .take(MAX_SUGGESTIONS)
+        .map(|(.., x)| x)
+        .collect()
+}
+
+/// Returns the Levenshtein distance between the strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, x) in a.bytes().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(x != *y);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,6 +176,39 @@ mod tests {
         assert_de_tokens(&Button(KeyCode::BTN_LEFT), &[Token::Str("BTN_LEFT")]);
     }
 
+    #[test]
+    fn test_de_alias() {
+        assert_de_tokens(&Button(KeyCode::BTN_MIDDLE), &[Token::Str("middle")]);
+        assert_de_tokens(&Button(KeyCode::BTN_SIDE), &[Token::Str("back")]);
+        assert_de_tokens(&Button(KeyCode::BTN_EXTRA), &[Token::Str("forward")]);
+    }
+
+    #[test]
+    fn test_de_code() {
+        assert_de_tokens(&Button::from_code(0x118), &[Token::Str("0x118")]);
+        assert_de_tokens(&Button::from_code(0x118), &[Token::Str("280")]);
+        assert_de_tokens(&Button::from_code(0x118), &[Token::U64(280)]);
+        assert_de_tokens(&Button::from_code(0x13e), &[Token::Str("0x13E")]);
+        assert_de_tokens_error::<Button>(&[Token::Str("0x140")], "unexpected button value 0x140");
+        assert_de_tokens_error::<Button>(&[Token::Str("0x2C0")], "unexpected button value 0x2C0");
+        assert!(serde_yaml::from_str::<Button>("BTN_TOUCH").is_err());
+        assert!(serde_yaml::from_str::<Button>("BTN_TRIGGER_HAPPY1").is_err());
+        assert_de_tokens_error::<Button>(&[Token::Str("0x1e")], "unexpected button value 0x1e");
+        assert_de_tokens_error::<Button>(&[Token::U64(0x1e0)], "unexpected button value 480");
+    }
+
+    #[test]
+    fn test_de_number() {
+        assert_de_tokens(&Button(KeyCode::BTN_LEFT), &[Token::U64(1)]);
+        assert_de_tokens(&Button(KeyCode::BTN_MIDDLE), &[Token::Str("2")]);
+        assert_de_tokens(&Button(KeyCode::BTN_RIGHT), &[Token::I64(3)]);
+        assert_de_tokens(&Button(KeyCode::BTN_SIDE), &[Token::U64(8)]);
+        assert_de_tokens(&Button(KeyCode::BTN_TASK), &[Token::U64(12)]);
+        assert_de_tokens_error::<Button>(&[Token::U64(4)], "unexpected button value 4");
+        assert_de_tokens_error::<Button>(&[Token::U64(21)], "unexpected button value 21");
+        assert_de_tokens_error::<Button>(&[Token::I64(-1)], "unexpected button value -1");
+    }
+
     #[test]
     fn test_de_key() {
         assert_de_tokens_error::<Button>(&[Token::Str("KEY_A")], "unexpected button value KEY_A");
@@ -49,7 +218,18 @@ mod tests {
     fn test_de_invalid_button() {
         assert_de_tokens_error::<Button>(
             &[Token::Str("BTN_BAD")],
-            "unexpected button value BTN_BAD",
+            "unexpected button value BTN_BAD, did you mean BTN_BACK, BTN_BASE, BTN_DEAD?",
         );
+        assert_de_tokens_error::<Button>(
+            &[Token::Str("btn_left")],
+            "unexpected button value btn_left, did you mean BTN_LEFT?",
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("BTN_BAD", "BTN_BACK"), 2);
     }
 }
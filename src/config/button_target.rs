@@ -0,0 +1,180 @@
+use std::fmt;
+
+use serde::de::value::{MapAccessDeserializer, StrDeserializer};
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use super::button::Button;
+use super::scroll_direction::ScrollDirection;
+
+const DEFAULT_SCROLL_RATE: f64 = 10.0;
+
+/// Target of a button mapping.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ButtonTarget {
+    /// Emits the button.
+    Button(Button),
+    /// Emits a wheel notch on press.
+    Wheel(ScrollDirection),
+    /// Scrolls continuously at `rate` notches per second while held.
+    Scroll {
+        direction: ScrollDirection,
+        rate: f64,
+    },
+    /// Drops the press and release.
+    None,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawScroll {
+    scroll: ScrollDirection,
+    #[serde(default = "default_scroll_rate")]
+    rate: f64,
+}
+
+fn default_scroll_rate() -> f64 {
+    DEFAULT_SCROLL_RATE
+}
+
+impl<'de> Deserialize<'de> for ButtonTarget {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ButtonTargetVisitor)
+    }
+}
+
+struct ButtonTargetVisitor;
+
+impl<'de> Visitor<'de> for ButtonTargetVisitor {
+    type Value = ButtonTarget;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a button, wheel direction, scroll or none")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        if v == "none" {
+            return Ok(ButtonTarget::None);
+        }
+        if let Some(x) = v.strip_prefix("wheel_") {
+            return ScrollDirection::deserialize(StrDeserializer::<E>::new(x))
+                .map(ButtonTarget::Wheel);
+        }
+        v.parse().map(ButtonTarget::Button).map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Button::from_number(v)
+            .map(ButtonTarget::Button)
+            .map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        u64::try_from(v)
+            .map_err(|_| E::custom(format!("unexpected button value {}", v)))
+            .and_then(|x| self.visit_u64(x))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        let raw = RawScroll::deserialize(MapAccessDeserializer::new(map))?;
+        if !(raw.rate > 0.0 && raw.rate.is_finite()) {
+            return Err(de::Error::custom(
+                "scroll rate must be a positive finite number",
+            ));
+        }
+        Ok(ButtonTarget::Scroll {
+            direction: raw.scroll,
+            rate: raw.rate,
+        })
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(ButtonTarget::None)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(ButtonTarget::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+    use serde_test::{Token, assert_de_tokens, assert_de_tokens_error};
+
+    use super::*;
+
+    #[test]
+    fn test_de_button() {
+        assert_de_tokens(
+            &ButtonTarget::Button(Button::from_code(KeyCode::BTN_LEFT.code())),
+            &[Token::Str("BTN_LEFT")],
+        );
+    }
+
+    #[test]
+    fn test_de_button_number() {
+        assert_de_tokens(
+            &ButtonTarget::Button(Button::from_code(KeyCode::BTN_SIDE.code())),
+            &[Token::U64(8)],
+        );
+        assert_de_tokens(
+            &ButtonTarget::Button(Button::from_code(KeyCode::BTN_RIGHT.code())),
+            &[Token::I64(3)],
+        );
+        assert_de_tokens_error::<ButtonTarget>(&[Token::I64(-1)], "unexpected button value -1");
+    }
+
+    #[test]
+    fn test_de_none() {
+        assert_de_tokens(&ButtonTarget::None, &[Token::None]);
+        assert_de_tokens(&ButtonTarget::None, &[Token::Unit]);
+        assert_de_tokens(&ButtonTarget::None, &[Token::Str("none")]);
+    }
+
+    #[test]
+    fn test_de_wheel() {
+        assert_de_tokens(
+            &ButtonTarget::Wheel(ScrollDirection::Up),
+            &[Token::Str("wheel_up")],
+        );
+        assert_de_tokens(
+            &ButtonTarget::Wheel(ScrollDirection::Right),
+            &[Token::Str("wheel_right")],
+        );
+    }
+
+    #[test]
+    fn test_de_scroll() {
+        assert_eq!(
+            serde_yaml::from_str::<ButtonTarget>("{scroll: down, rate: 5}").unwrap(),
+            ButtonTarget::Scroll {
+                direction: ScrollDirection::Down,
+                rate: 5.0
+            }
+        );
+        assert_eq!(
+            serde_yaml::from_str::<ButtonTarget>("{scroll: left}").unwrap(),
+            ButtonTarget::Scroll {
+                direction: ScrollDirection::Left,
+                rate: 10.0
+            }
+        );
+        assert!(serde_yaml::from_str::<ButtonTarget>("{scroll: down, rate: 0}").is_err());
+    }
+
+    #[test]
+    fn test_de_invalid() {
+        assert_de_tokens_error::<ButtonTarget>(
+            &[Token::Str("KEY_A")],
+            "unexpected button value KEY_A",
+        );
+        assert_de_tokens_error::<ButtonTarget>(
+            &[Token::Str("wheel_bad")],
+            "unknown variant `bad`, expected one of `up`, `down`, `left`, `right`",
+        );
+    }
+}
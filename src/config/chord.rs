@@ -0,0 +1,122 @@
+use serde::Deserialize;
+
+use super::button::Button;
+
+/// A set of buttons which produce another button when pressed together.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "RawChord")]
+pub struct Chord {
+    /// Source buttons which must be pressed together.
+    pub buttons: Vec<Button>,
+    /// Button emitted instead of the source buttons.
+    pub target: Button,
+}
+
+#[derive(Deserialize)]
+struct RawChord {
+    buttons: Vec<Button>,
+    target: Button,
+}
+
+impl TryFrom<RawChord> for Chord {
+    type Error = TryFromRawChordError;
+
+    fn try_from(value: RawChord) -> Result<Self, Self::Error> {
+        let mut buttons = Vec::new();
+        for x in value.buttons {
+            if !buttons.contains(&x) {
+                buttons.push(x);
+            }
+        }
+        if buttons.len() < 2 {
+            return Err(TryFromRawChordError());
+        }
+        Ok(Chord {
+            buttons,
+            target: value.target,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("chord must have at least two distinct buttons")]
+pub struct TryFromRawChordError();
+
+impl Chord {
+    /// Returns true if `buttons` are exactly the buttons of this chord.
+    pub fn matches(&self, buttons: &[Button]) -> bool {
+        self.buttons.len() == buttons.len() && self.contains_all(buttons)
+    }
+
+    /// Returns true if all of `buttons` are part of this chord.
+    pub fn contains_all(&self, buttons: &[Button]) -> bool {
+        buttons.iter().all(|x| self.buttons.contains(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+    use serde_test::{Token, assert_de_tokens, assert_de_tokens_error};
+
+    use super::*;
+
+    fn button(code: KeyCode) -> Button {
+        Button::from_code(code.code())
+    }
+
+    fn chord_tokens(buttons: &[&'static str]) -> Vec<Token> {
+        let mut tokens = vec![
+            Token::Struct {
+                name: "RawChord",
+                len: 2,
+            },
+            Token::Str("buttons"),
+            Token::Seq {
+                len: Some(buttons.len()),
+            },
+        ];
+        tokens.extend(buttons.iter().map(|x| Token::Str(x)));
+        tokens.extend([
+            Token::SeqEnd,
+            Token::Str("target"),
+            Token::Str("BTN_MIDDLE"),
+            Token::StructEnd,
+        ]);
+        tokens
+    }
+
+    #[test]
+    fn test_de() {
+        assert_de_tokens(
+            &Chord {
+                buttons: vec![button(KeyCode::BTN_LEFT), button(KeyCode::BTN_RIGHT)],
+                target: button(KeyCode::BTN_MIDDLE),
+            },
+            &chord_tokens(&["BTN_LEFT", "BTN_RIGHT", "BTN_LEFT"]),
+        );
+    }
+
+    #[test]
+    fn test_de_too_few_buttons() {
+        let error = "chord must have at least two distinct buttons";
+        assert_de_tokens_error::<Chord>(&chord_tokens(&["BTN_LEFT"]), error);
+        assert_de_tokens_error::<Chord>(&chord_tokens(&["BTN_LEFT", "BTN_LEFT"]), error);
+    }
+
+    #[test]
+    fn test_matches() {
+        let chord = Chord {
+            buttons: vec![button(KeyCode::BTN_LEFT), button(KeyCode::BTN_RIGHT)],
+            target: button(KeyCode::BTN_MIDDLE),
+        };
+        let left = button(KeyCode::BTN_LEFT);
+        let right = button(KeyCode::BTN_RIGHT);
+        let side = button(KeyCode::BTN_SIDE);
+        assert!(chord.matches(&[right, left]));
+        assert!(!chord.matches(&[left]));
+        assert!(!chord.matches(&[left, side]));
+        assert!(chord.contains_all(&[left]));
+        assert!(!chord.contains_all(&[left, side]));
+    }
+}
@@ -0,0 +1,44 @@
+use serde::Deserialize;
+
+/// Travel in hi-res units (120 per notch) which motion scrolling must exceed before it scrolls.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "f64")]
+pub struct DeadZone(f64);
+
+impl TryFrom<f64> for DeadZone {
+    type Error = TryFromFloatError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if value >= 0.0 && value.is_finite() {
+            Ok(DeadZone(value))
+        } else {
+            Err(TryFromFloatError())
+        }
+    }
+}
+
+impl DeadZone {
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("dead zone must be a non-negative finite number")]
+pub struct TryFromFloatError();
+
+#[cfg(test)]
+mod tests {
+    use serde_test::{Token, assert_de_tokens, assert_de_tokens_error};
+
+    use super::*;
+
+    #[test]
+    fn test_de() {
+        let error = "dead zone must be a non-negative finite number";
+        assert_de_tokens(&DeadZone(30.0), &[Token::F64(30.0)]);
+        assert_de_tokens(&DeadZone(0.0), &[Token::F64(0.0)]);
+        assert_de_tokens_error::<DeadZone>(&[Token::F64(-1.0)], error);
+        assert_de_tokens_error::<DeadZone>(&[Token::F64(f64::INFINITY)], error);
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::button::Button;
+
+/// Debounce time in milliseconds for all buttons or for each button.
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Debounce {
+    All(u64),
+    PerButton(HashMap<Button, u64>),
+}
+
+impl Debounce {
+    /// Returns the debounce time of the button in microseconds.
+    pub fn time_usec(&self, button: Button) -> Option<u64> {
+        match self {
+            Debounce::All(x) => Some(*x),
+            Debounce::PerButton(x) => x.get(&button).copied(),
+        }
+        .map(|x| x * 1000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+
+    fn button(code: KeyCode) -> Button {
+        Button::from_code(code.code())
+    }
+
+    #[test]
+    fn test_de() {
+        assert_eq!(
+            serde_yaml::from_str::<Debounce>("30").unwrap(),
+            Debounce::All(30)
+        );
+        assert_eq!(
+            serde_yaml::from_str::<Debounce>("BTN_LEFT: 50").unwrap(),
+            Debounce::PerButton(HashMap::from([(button(KeyCode::BTN_LEFT), 50)]))
+        );
+    }
+
+    #[test]
+    fn test_time_usec() {
+        let left = button(KeyCode::BTN_LEFT);
+        let right = button(KeyCode::BTN_RIGHT);
+        assert_eq!(Debounce::All(30).time_usec(left), Some(30_000));
+
+        let per_button = Debounce::PerButton(HashMap::from([(left, 50)]));
+        assert_eq!(per_button.time_usec(left), Some(50_000));
+        assert_eq!(per_button.time_usec(right), None);
+    }
+}
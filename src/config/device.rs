@@ -1,28 +1,80 @@
 use std::collections::HashMap;
+use std::num::NonZeroU64;
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 use crate::config::scroll_factor::ScrollFactorPair;
 use crate::errors::Error;
 
 use super::accel_profile::AccelProfile;
+use super::autoscroll::Autoscroll;
+use super::axis_lock::AxisLock;
 use super::button::Button;
+use super::button_target::ButtonTarget;
+use super::chord::Chord;
+use super::dead_zone::DeadZone;
+use super::debounce::Debounce;
 use super::device_info::DeviceInfo;
+use super::factor::Factor;
+use super::kinetic_scroll::KineticScroll;
+use super::layer::{Layer, deserialize_layers};
 use super::match_rule::MatchRule;
+use super::motion_factor::MotionFactorPair;
+use super::ratchet::Ratchet;
+use super::repeat::Repeat;
+use super::scroll_direction::ScrollDirection;
+use super::wheel_mapping::WheelMapping;
+use super::wheel_output::WheelOutput;
+
+const DEFAULT_CHORD_WINDOW_MS: u64 = 50;
+const DEFAULT_PRECISION_FACTOR: f64 = 0.25;
 
 #[derive(Debug, Default, Deserialize, PartialEq)]
 pub struct Device {
     pub match_rule: MatchRule,
 
-    /// Sets the pointer acceleration profile to the given profile. Permitted values are `adaptive`, `flat`.  Not all devices support this option or all profiles. If a profile is unsupported, the default profile for this device is used. For a description on the profiles and their behavior, see the libinput documentation.
+    /// Sets the pointer acceleration profile to the given profile. Permitted values are `adaptive`, `flat` and a map of `custom` curves.  Not all devices support this option or all profiles. If a profile is unsupported, the default profile for this device is used. For a description on the profiles and their behavior, see the libinput documentation. With `custom`, ponde itself accelerates the unaccelerated motion, as libinput's own custom profile is not available to ponde. Pointer motion uses the curve of the `motion` type, or else of the `fallback` type, each given by `points` of the output speed at every `step` of the input speed in units per millisecond (e.g. `{custom: {motion: {points: [0, 1, 3, 6], step: 1}}}`). `accel_speed` has no effect with `custom`.
     pub accel_profile: Option<AccelProfile>,
 
     /// Sets the pointer acceleration speed within the range [-1, 1]
     pub accel_speed: Option<f64>,
 
-    /// Sets the logical button mapping for this device.
+    /// Enables Windows-style autoscroll. A click of `button` anchors the pointer, and until the next click, pointer motion does not move the cursor but scrolls continuously at a speed proportional to the distance from the anchor.
+    pub autoscroll: Option<Autoscroll>,
+
+    /// Locks each motion scroll gesture to its dominant axis, which is picked once the gesture scrolls `distance`. Both axes are scrolled if the minor axis scrolled at least `diagonal_ratio` of the dominant one.
+    pub axis_lock: Option<AxisLock>,
+
+    /// Sets the logical button mapping for this device. Mapping to `none` or `null` drops the press and release of the button. Mapping to `wheel_up`, `wheel_down`, `wheel_left` or `wheel_right` emits a wheel notch on press. Mapping to `{scroll: <direction>, rate: <notches per second>}` scrolls continuously while the button is held (default rate 10). Wheel targets use `wheel_scroll_factor`.
+    #[serde(default)]
+    pub button_mapping: HashMap<Button, ButtonTarget>,
+
+    /// Sets the time window in milliseconds in which chord buttons must be pressed together (default 50). Presses of chord buttons are held back until the window expires.
+    pub chord_window_ms: Option<u64>,
+
+    /// Sets button chords. Pressing all `buttons` of a chord together within `chord_window_ms` emits its `target` button instead. The target is treated as if pressed on the device, so `button_mapping` still applies to it.
     #[serde(default)]
-    pub button_mapping: HashMap<Button, Button>,
+    pub chords: Vec<Chord>,
+
+    /// Sets the debounce time in milliseconds, for all buttons or for each button as a map. Presses which arrive within the time after a release of the same button are swallowed with their releases.
+    pub debounce_ms: Option<Debounce>,
+
+    /// Disables the buttons. Their presses and releases are dropped.
+    #[serde(default)]
+    pub disabled_buttons: Vec<Button>,
+
+    /// Enables ClickLock for `BTN_LEFT`. Holding the button for the given time in milliseconds latches it down until the next click.
+    pub drag_lock_ms: Option<NonZeroU64>,
+
+    /// Makes the vertical wheel scroll horizontally while the button is held. The press and release of the button are dropped if the wheel is used meanwhile, and otherwise emitted together on release. The button must not also be a `wheel_mapping` modifier.
+    pub horizontal_scroll_modifier: Option<Button>,
+
+    /// Keeps scrolling after motion scrolling stops, e.g. on release of `scroll_button`, with velocity decaying by `friction` until it falls below `min_velocity`. Any new pointer event stops it.
+    pub kinetic_scroll: Option<KineticScroll>,
+
+    /// Sets named layers. While a layer is active, its `button_mapping` and scroll settings are used instead of the device's ones.
+    #[serde(default, deserialize_with = "deserialize_layers")]
+    pub layers: HashMap<String, Layer>,
 
     /// Enables left-handed button orientation, i.e. swapping left and right buttons.
     pub left_handed: Option<bool>,
@@ -30,9 +82,26 @@ pub struct Device {
     /// Enables middle button emulation. When enabled, pressing the left and right buttons simultaneously produces a middle mouse button click.
     pub middle_emulation: Option<bool>,
 
+    /// Sets the pointer motion factor for horizontal (`x`) and vertical (`y`) motion, e.g. to even out an anisotropic trackball. Fractions of the scaled motion are carried over to the next event.
+    #[serde(default)]
+    pub motion_factor: MotionFactorPair,
+
     /// Enables or disables natural scrolling behavior.
     pub natural_scrolling: Option<bool>,
 
+    /// Designates a button which scales pointer motion by `precision_factor` while held, for pixel-exact work. The press and release of the button are dropped.
+    pub precision_button: Option<Button>,
+
+    /// Enables or disables the precision button lock. If enabled, each click of `precision_button` toggles the precision mode instead.
+    pub precision_button_lock: Option<bool>,
+
+    /// Sets the pointer motion factor while the precision mode is on (default 0.25, must be > 0.0).
+    pub precision_factor: Option<Factor>,
+
+    /// Repeats clicks while the buttons are held, like keyboard repeat. Each value has `delay_ms` before the first repeat (default 500) and `interval_ms` between repeats.
+    #[serde(default)]
+    pub repeat: HashMap<Button, Repeat>,
+
     /// Sets the rotation angle of the device to the given angle, in degrees clockwise. The angle must be between 0 (inclusive) and 360 (exclusive).
     pub rotation_angle: Option<u32>,
 
@@ -42,19 +111,43 @@ pub struct Device {
     /// Enables or disables the scroll button lock. If enabled, the `scroll_button` is considered logically down after the first click and remains down until the second click of that button. If disabled (the default), the `scroll_button` is considered logically down while held down and up once physically released.
     pub scroll_button_lock: Option<bool>,
 
+    /// Spreads each wheel scroll into a series of hi-res scrolls over the given time in milliseconds, easing out. Legacy wheel events are still emitted once per accumulated notch.
+    pub smooth_scroll_ms: Option<NonZeroU64>,
+
+    /// Maps wheel directions to clicks of keys or buttons instead of scrolling. A mapping with `modifier` applies only while the button is held, and takes precedence over one without it. The press and release of a modifier are dropped if it is used to map the wheel, and otherwise emitted together on release.
+    #[serde(default)]
+    pub wheel_mapping: Vec<WheelMapping>,
+
+    /// Sets the wheel events to emit. Permitted values are `both` (the default), `legacy` (`REL_WHEEL`/`REL_HWHEEL` only) and `hires` (`REL_WHEEL_HI_RES`/`REL_HWHEEL_HI_RES` only). The sink device advertises only the axes which some device emits.
+    #[serde(default)]
+    pub wheel_output: WheelOutput,
+
     /// Sets the wheel scroll speed factor for vertical and horizontal scrolling.
     #[serde(default)]
     pub wheel_scroll_factor: ScrollFactorPair,
 
-    /// Sets the motion scroll speed factor for vertical and horizontal scrolling (used with `scroll_button`).
+    /// Sets the motion scroll speed factor for vertical and horizontal scrolling (used with `scroll_button`). Motion is converted to 8 hi-res units per unit of libinput scroll value. Since this release, motion scrolling emits `REL_WHEEL`/`REL_HWHEEL` at 1/120 of the hi-res rate, i.e. once per 15 units of motion, where earlier releases emitted one per unit. Factors of 15 (`{vertical: 15, horizontal: 15}`) restore the old legacy speed, but also make hi-res scrolling 15 times faster, so use them with `wheel_output: legacy`.
     #[serde(default)]
     pub motion_scroll_factor: ScrollFactorPair,
+
+    /// Sets the travel in hi-res units (120 per notch) which each motion scroll gesture must exceed before it scrolls, so that jitter does not scroll.
+    pub motion_scroll_dead_zone: Option<DeadZone>,
+
+    /// Emits motion scrolling only in whole notches, one for each `distance` of travel, like a detented wheel.
+    pub motion_scroll_ratchet: Option<Ratchet>,
+
+    /// Makes wheel and motion scrolling zoom while the button is held, by holding `KEY_LEFTCTRL` down from the first scroll until the button is released. The press and release of the button are dropped if it is used to zoom, and otherwise emitted together on release. The button must not also be `horizontal_scroll_modifier` or a `wheel_mapping` modifier.
+    pub zoom_modifier: Option<Button>,
 }
 
 impl Device {
     pub fn apply_to(&self, device: &mut input::Device) -> Result<(), Error> {
-        if let Some(x) = self.accel_profile {
-            device.config_accel_set_profile(x.into())?;
+        if let Some(x) = self
+            .accel_profile
+            .as_ref()
+            .and_then(AccelProfile::libinput_profile)
+        {
+            device.config_accel_set_profile(x)?;
         }
 
         if let Some(x) = self.accel_speed {
@@ -93,11 +186,102 @@ impl Device {
         Ok(())
     }
 
-    pub fn map_button(&self, button: Button) -> Button {
-        self.button_mapping.get(&button).copied().unwrap_or(button)
+    pub fn chord_window_usec(&self) -> u64 {
+        self.chord_window_ms.unwrap_or(DEFAULT_CHORD_WINDOW_MS) * 1000
+    }
+
+    pub fn drag_lock_usec(&self) -> Option<u64> {
+        self.drag_lock_ms.map(|x| x.get().saturating_mul(1000))
+    }
+
+    pub fn smooth_scroll_usec(&self) -> u64 {
+        self.smooth_scroll_ms.map_or(0, |x| x.get() * 1000)
+    }
+
+    pub fn precision_factor(&self) -> f64 {
+        self.precision_factor
+            .as_ref()
+            .map_or(DEFAULT_PRECISION_FACTOR, Factor::value)
+    }
+
+    pub fn map_button(&self, button: Button, layer: Option<&Layer>) -> ButtonTarget {
+        if let Some(x) = layer.and_then(|x| x.button_mapping.get(&button)) {
+            return *x;
+        }
+        if self.disabled_buttons.contains(&button) {
+            return ButtonTarget::None;
+        }
+        self.button_mapping
+            .get(&button)
+            .copied()
+            .unwrap_or(ButtonTarget::Button(button))
+    }
+
+    /// Returns the names of two kinds of modifiers which have the same button.
+    fn shared_modifier(&self) -> Option<(&'static str, &'static str)> {
+        let modifiers = [
+            ("zoom_modifier", self.zoom_modifier),
+            (
+                "horizontal_scroll_modifier",
+                self.horizontal_scroll_modifier,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, x)| Some((name, x?)))
+        .chain(
+            self.wheel_mapping
+                .iter()
+                .filter_map(|x| Some(("wheel_mapping modifier", x.modifier?))),
+        )
+        .collect::<Vec<_>>();
+        modifiers.iter().enumerate().find_map(|(i, (a, x))| {
+            modifiers[i + 1..]
+                .iter()
+                .find(|(b, y)| a != b && x == y)
+                .map(|(b, _)| (*a, *b))
+        })
+    }
+
+    /// Returns true if the button is a modifier of some wheel mapping.
+    pub fn is_wheel_modifier(&self, button: Button) -> bool {
+        self.wheel_mapping
+            .iter()
+            .any(|x| x.modifier == Some(button))
+    }
+
+    /// Returns the wheel mapping of the direction, preferring one whose modifier is held.
+    pub fn map_wheel(
+        &self,
+        direction: ScrollDirection,
+        is_held: impl Fn(Button) -> bool,
+    ) -> Option<&WheelMapping> {
+        let mut mappings = self
+            .wheel_mapping
+            .iter()
+            .filter(|x| x.direction == direction);
+        mappings
+            .clone()
+            .find(|x| x.modifier.is_some_and(&is_held))
+            .or_else(|| mappings.find(|x| x.modifier.is_none()))
     }
 
     pub fn matches(&self, device_info: &DeviceInfo) -> bool {
         self.match_rule.matches(device_info)
     }
 }
+/// Deserializes devices, rejecting a button which is a modifier of more than one kind, as it would be ambiguous what it modifies.
+pub fn deserialize_devices<'de, D>(deserializer: D) -> Result<Vec<Device>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let devices = Vec::<Device>::deserialize(deserializer)?;
+    for x in &devices {
+        if let Some((a, b)) = x.shared_modifier() {
+            return Err(serde::de::Error::custom(format!(
+                "{} and {} have the same button",
+                a, b
+            )));
+        }
+    }
+    Ok(devices)
+}
@@ -0,0 +1,89 @@
+use std::str::FromStr;
+
+use evdev::KeyCode;
+use serde::{Deserialize, Deserializer};
+
+use super::button::BUTTON_CODES;
+
+/// A keyboard key or a button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key(KeyCode);
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let code = KeyCode::from_str(&s)
+            .ok()
+            .filter(|_| s.starts_with("KEY_") || s.starts_with("BTN_"))
+            .ok_or_else(|| serde::de::Error::custom(format!("unexpected key value {}", s)))?;
+        // The sink device advertises only keyboard keys and pointer buttons.
+        let is_emitted = if s.starts_with("KEY_") {
+            is_keyboard_key(code.code())
+        } else {
+            BUTTON_CODES.contains(&code.code())
+        };
+        if !is_emitted {
+            return Err(serde::de::Error::custom(format!(
+                "key value {} cannot be emitted",
+                s
+            )));
+        }
+        Ok(Self(code))
+    }
+}
+
+impl Key {
+    pub fn code(&self) -> u16 {
+        self.0.code()
+    }
+
+    pub fn is_keyboard_key(&self) -> bool {
+        is_keyboard_key(self.code())
+    }
+}
+
+/// Returns true if the key code is a keyboard key, which is emitted from the keyboard device, not a button.
+pub fn is_keyboard_key(code: u16) -> bool {
+    (KeyCode::KEY_ESC.code()..KeyCode::BTN_0.code()).contains(&code)
+        || (KeyCode::KEY_OK.code()..KeyCode::BTN_TRIGGER_HAPPY1.code()).contains(&code)
+            && !(KeyCode::BTN_DPAD_UP.code()..=KeyCode::BTN_DPAD_RIGHT.code()).contains(&code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_test::{Token, assert_de_tokens, assert_de_tokens_error};
+
+    #[test]
+    fn test_de_key() {
+        assert_de_tokens(&Key(KeyCode::KEY_VOLUMEUP), &[Token::Str("KEY_VOLUMEUP")]);
+    }
+
+    #[test]
+    fn test_de_button() {
+        assert_de_tokens(&Key(KeyCode::BTN_BACK), &[Token::Str("BTN_BACK")]);
+    }
+
+    #[test]
+    fn test_de_invalid_key() {
+        assert_de_tokens_error::<Key>(&[Token::Str("KEY_BAD")], "unexpected key value KEY_BAD");
+    }
+
+    #[test]
+    fn test_de_not_emitted() {
+        for name in [
+            "BTN_DPAD_UP",
+            "BTN_TRIGGER_HAPPY1",
+            "BTN_TOUCH",
+            "BTN_TOOL_PEN",
+        ] {
+            assert_de_tokens_error::<Key>(
+                &[Token::Str(name)],
+                &format!("key value {} cannot be emitted", name),
+            );
+        }
+    }
+}
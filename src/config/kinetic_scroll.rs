@@ -0,0 +1,75 @@
+use serde::Deserialize;
+
+const DEFAULT_FRICTION: f64 = 4.0;
+const DEFAULT_MIN_VELOCITY: f64 = 60.0;
+
+/// Keeps scrolling with decaying velocity after motion scrolling stops.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "RawKineticScroll")]
+pub struct KineticScroll {
+    /// Exponential decay rate of the velocity per second.
+    pub friction: f64,
+    /// Velocity in hi-res units (120 per notch) per second below which scrolling stops.
+    pub min_velocity: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawKineticScroll {
+    #[serde(default = "default_friction")]
+    friction: f64,
+    #[serde(default = "default_min_velocity")]
+    min_velocity: f64,
+}
+
+fn default_friction() -> f64 {
+    DEFAULT_FRICTION
+}
+
+fn default_min_velocity() -> f64 {
+    DEFAULT_MIN_VELOCITY
+}
+
+impl TryFrom<RawKineticScroll> for KineticScroll {
+    type Error = TryFromRawKineticScrollError;
+
+    fn try_from(value: RawKineticScroll) -> Result<Self, Self::Error> {
+        let is_valid = |x: f64| x > 0.0 && x.is_finite();
+        if !is_valid(value.friction) || !is_valid(value.min_velocity) {
+            return Err(TryFromRawKineticScrollError());
+        }
+        Ok(KineticScroll {
+            friction: value.friction,
+            min_velocity: value.min_velocity,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("friction and min_velocity must be positive finite numbers")]
+pub struct TryFromRawKineticScrollError();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de() {
+        assert_eq!(
+            serde_yaml::from_str::<KineticScroll>("{}").unwrap(),
+            KineticScroll {
+                friction: 4.0,
+                min_velocity: 60.0,
+            }
+        );
+        assert_eq!(
+            serde_yaml::from_str::<KineticScroll>("{friction: 2, min_velocity: 120}").unwrap(),
+            KineticScroll {
+                friction: 2.0,
+                min_velocity: 120.0,
+            }
+        );
+        assert!(serde_yaml::from_str::<KineticScroll>("friction: 0").is_err());
+        assert!(serde_yaml::from_str::<KineticScroll>("min_velocity: -1").is_err());
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer};
+
+use super::button::Button;
+use super::button_target::ButtonTarget;
+use super::scroll_factor::ScrollFactorPair;
+
+/// Alternate mapping and scroll settings which are used while the layer is active.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Layer {
+    /// Button which activates the layer. The press and release of the button are not forwarded. Each layer must have its own button.
+    pub button: Button,
+
+    /// Activation mode of the layer.
+    #[serde(default)]
+    pub mode: LayerMode,
+
+    /// Button mapping which takes precedence over the device's `button_mapping`.
+    #[serde(default)]
+    pub button_mapping: HashMap<Button, ButtonTarget>,
+
+    /// Wheel scroll speed factor which replaces the device's `wheel_scroll_factor`.
+    pub wheel_scroll_factor: Option<ScrollFactorPair>,
+
+    /// Motion scroll speed factor which replaces the device's `motion_scroll_factor`.
+    pub motion_scroll_factor: Option<ScrollFactorPair>,
+
+    /// Makes the vertical wheel emit horizontal scroll.
+    #[serde(default)]
+    pub horizontal_wheel: bool,
+}
+
+/// Deserializes layers, rejecting layers which share a button, as it would be ambiguous which one to activate.
+pub fn deserialize_layers<'de, D>(deserializer: D) -> Result<HashMap<String, Layer>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let layers = HashMap::<String, Layer>::deserialize(deserializer)?;
+    let mut names = layers.keys().collect::<Vec<_>>();
+    names.sort();
+    for (i, a) in names.iter().enumerate() {
+        if let Some(b) = names[i + 1..]
+            .iter()
+            .find(|b| layers[**b].button == layers[*a].button)
+        {
+            return Err(serde::de::Error::custom(format!(
+                "layers {} and {} have the same button",
+                a, b
+            )));
+        }
+    }
+    Ok(layers)
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LayerMode {
+    /// Active while the button is held.
+    #[default]
+    Hold,
+    /// Each press of the button toggles the layer.
+    Toggle,
+    /// Active from a press of the button until the next press of another button.
+    OneShot,
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+
+    #[test]
+    fn test_de() {
+        let layer = serde_yaml::from_str::<Layer>(
+            r#"
+            button: BTN_EXTRA
+            mode: one_shot
+            button_mapping:
+              BTN_LEFT: BTN_SIDE
+            horizontal_wheel: true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            layer,
+            Layer {
+                button: Button::from_code(KeyCode::BTN_EXTRA.code()),
+                mode: LayerMode::OneShot,
+                button_mapping: HashMap::from([(
+                    Button::from_code(KeyCode::BTN_LEFT.code()),
+                    ButtonTarget::Button(Button::from_code(KeyCode::BTN_SIDE.code()))
+                )]),
+                wheel_scroll_factor: None,
+                motion_scroll_factor: None,
+                horizontal_wheel: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_de_default_mode() {
+        let layer = serde_yaml::from_str::<Layer>("button: BTN_EXTRA").unwrap();
+        assert_eq!(layer.mode, LayerMode::Hold);
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Layers(#[serde(deserialize_with = "deserialize_layers")] HashMap<String, Layer>);
+
+    #[test]
+    fn test_de_layers() {
+        let layers =
+            serde_yaml::from_str::<Layers>("{a: {button: BTN_SIDE}, b: {button: BTN_EXTRA}}")
+                .unwrap();
+        assert_eq!(layers.0.len(), 2);
+
+        let err = serde_yaml::from_str::<Layers>(
+            "{c: {button: BTN_SIDE}, a: {button: BTN_EXTRA}, b: {button: BTN_SIDE}}",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("layers b and c have the same button")
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use serde::Deserialize;
+
+use super::factor::Factor;
+
+/// Pointer motion factor x/y pair.
+#[derive(Debug, Deserialize, Default, PartialEq)]
+pub struct MotionFactorPair {
+    /// Horizontal motion factor.
+    #[serde(default)]
+    pub x: Factor,
+    /// Vertical motion factor.
+    #[serde(default)]
+    pub y: Factor,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de_pair() {
+        assert_eq!(
+            serde_yaml::from_str::<MotionFactorPair>("{x: 0.8}").unwrap(),
+            MotionFactorPair {
+                x: Factor::try_from(0.8).unwrap(),
+                y: Factor::default(),
+            }
+        );
+        assert!(serde_yaml::from_str::<MotionFactorPair>("{y: 0}").is_err());
+    }
+}
@@ -0,0 +1,47 @@
+use serde::Deserialize;
+
+/// Quantizes motion scrolling into whole notches.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "RawRatchet")]
+pub struct Ratchet {
+    /// Motion scroll distance in hi-res units (120 per notch), scaled by `motion_scroll_factor`, per emitted notch.
+    pub distance: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRatchet {
+    distance: f64,
+}
+
+impl TryFrom<RawRatchet> for Ratchet {
+    type Error = TryFromRawRatchetError;
+
+    fn try_from(value: RawRatchet) -> Result<Self, Self::Error> {
+        if !(value.distance > 0.0 && value.distance.is_finite()) {
+            return Err(TryFromRawRatchetError());
+        }
+        Ok(Ratchet {
+            distance: value.distance,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("ratchet distance must be a positive finite number")]
+pub struct TryFromRawRatchetError();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de() {
+        assert_eq!(
+            serde_yaml::from_str::<Ratchet>("distance: 60").unwrap(),
+            Ratchet { distance: 60.0 }
+        );
+        assert!(serde_yaml::from_str::<Ratchet>("distance: 0").is_err());
+        assert!(serde_yaml::from_str::<Ratchet>("{}").is_err());
+    }
+}
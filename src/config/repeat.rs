@@ -0,0 +1,46 @@
+use std::num::NonZeroU64;
+
+use serde::Deserialize;
+
+const DEFAULT_DELAY_MS: u64 = 500;
+
+/// Repeats clicks while a button is held.
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct Repeat {
+    /// Initial delay in milliseconds before the first repeat (default 500).
+    #[serde(default = "default_delay_ms")]
+    pub delay_ms: u64,
+    /// Interval in milliseconds between repeats.
+    pub interval_ms: NonZeroU64,
+}
+
+fn default_delay_ms() -> u64 {
+    DEFAULT_DELAY_MS
+}
+
+impl Repeat {
+    pub fn delay_usec(&self) -> u64 {
+        self.delay_ms * 1000
+    }
+
+    pub fn interval_usec(&self) -> u64 {
+        self.interval_ms.get() * 1000
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de() {
+        assert_eq!(
+            serde_yaml::from_str::<Repeat>("interval_ms: 100").unwrap(),
+            Repeat {
+                delay_ms: 500,
+                interval_ms: NonZeroU64::new(100).unwrap(),
+            }
+        );
+        assert!(serde_yaml::from_str::<Repeat>("interval_ms: 0").is_err());
+    }
+}
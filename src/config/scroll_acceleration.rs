@@ -0,0 +1,73 @@
+use serde::Deserialize;
+
+/// Scroll speed multiplier as a function of the scroll speed in notches per second.
+///
+/// The points of speed and multiplier are interpolated linearly, and the multiplier is constant beyond both ends.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "Vec<(f64, f64)>")]
+pub struct ScrollAcceleration(Vec<(f64, f64)>);
+
+impl TryFrom<Vec<(f64, f64)>> for ScrollAcceleration {
+    type Error = TryFromPointsError;
+
+    fn try_from(value: Vec<(f64, f64)>) -> Result<Self, Self::Error> {
+        let is_valid_point = |(speed, multiplier): &(f64, f64)| {
+            *speed >= 0.0 && speed.is_finite() && *multiplier > 0.0 && multiplier.is_finite()
+        };
+        if value.is_empty()
+            || !value.iter().all(is_valid_point)
+            || !value.windows(2).all(|x| x[0].0 < x[1].0)
+        {
+            return Err(TryFromPointsError());
+        }
+        Ok(ScrollAcceleration(value))
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error(
+    "acceleration must be a list of [speed, multiplier] points with increasing non-negative speeds and positive multipliers"
+)]
+pub struct TryFromPointsError();
+
+impl ScrollAcceleration {
+    pub fn multiplier(&self, speed: f64) -> f64 {
+        let upper = self.0.iter().position(|(x, _)| *x > speed);
+        match upper {
+            Some(0) => self.0[0].1,
+            Some(i) => {
+                let (x0, y0) = self.0[i - 1];
+                let (x1, y1) = self.0[i];
+                y0 + (y1 - y0) * (speed - x0) / (x1 - x0)
+            }
+            None => self.0[self.0.len() - 1].1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de() {
+        assert_eq!(
+            serde_yaml::from_str::<ScrollAcceleration>("[[0, 1], [10, 3]]").unwrap(),
+            ScrollAcceleration(vec![(0.0, 1.0), (10.0, 3.0)])
+        );
+        assert!(serde_yaml::from_str::<ScrollAcceleration>("[]").is_err());
+        assert!(serde_yaml::from_str::<ScrollAcceleration>("[[10, 1], [5, 3]]").is_err());
+        assert!(serde_yaml::from_str::<ScrollAcceleration>("[[0, 0]]").is_err());
+        assert!(serde_yaml::from_str::<ScrollAcceleration>("[[-1, 1]]").is_err());
+    }
+
+    #[test]
+    fn test_multiplier() {
+        let acceleration = ScrollAcceleration(vec![(5.0, 1.0), (10.0, 3.0), (20.0, 4.0)]);
+        assert_eq!(acceleration.multiplier(0.0), 1.0);
+        assert_eq!(acceleration.multiplier(5.0), 1.0);
+        assert_eq!(acceleration.multiplier(7.5), 2.0);
+        assert_eq!(acceleration.multiplier(15.0), 3.5);
+        assert_eq!(acceleration.multiplier(100.0), 4.0);
+    }
+}
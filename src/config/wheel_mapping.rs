@@ -0,0 +1,16 @@
+use serde::Deserialize;
+
+use super::button::Button;
+use super::key::Key;
+use super::scroll_direction::ScrollDirection;
+
+/// Maps a wheel direction to clicks of a key or button.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct WheelMapping {
+    /// Wheel direction to map.
+    pub direction: ScrollDirection,
+    /// Key or button clicked for each notch.
+    pub target: Key,
+    /// Button which must be held for the mapping to apply.
+    pub modifier: Option<Button>,
+}
@@ -0,0 +1,54 @@
+use serde::Deserialize;
+
+/// Families of wheel events which are emitted.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WheelOutput {
+    /// `REL_WHEEL`/`REL_HWHEEL` and their hi-res counterparts.
+    #[default]
+    Both,
+    /// `REL_WHEEL`/`REL_HWHEEL` only.
+    Legacy,
+    /// `REL_WHEEL_HI_RES`/`REL_HWHEEL_HI_RES` only.
+    Hires,
+}
+
+impl WheelOutput {
+    pub fn legacy(self) -> bool {
+        self != Self::Hires
+    }
+
+    pub fn hi_res(self) -> bool {
+        self != Self::Legacy
+    }
+
+    /// Returns the output which includes both families of events.
+    pub fn union(self, other: Self) -> Self {
+        if self == other { self } else { Self::Both }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union() {
+        assert_eq!(
+            WheelOutput::Legacy.union(WheelOutput::Legacy),
+            WheelOutput::Legacy
+        );
+        assert_eq!(
+            WheelOutput::Hires.union(WheelOutput::Hires),
+            WheelOutput::Hires
+        );
+        assert_eq!(
+            WheelOutput::Legacy.union(WheelOutput::Hires),
+            WheelOutput::Both
+        );
+        assert_eq!(
+            WheelOutput::Both.union(WheelOutput::Hires),
+            WheelOutput::Both
+        );
+    }
+}
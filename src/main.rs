@@ -30,7 +30,9 @@ fn main() -> anyhow::Result<()> {
 
     let args = Args::parse();
     let config = Config::load(&args.config_file).context("failed to load config")?;
-    let sink_device = SinkDevice::create("ponde").context("failed to create sink device")?;
+    let sink_device =
+        SinkDevice::create("ponde", config.wheel_output(), config.has_keyboard_keys())
+            .context("failed to create sink device")?;
     let mut app = App::new(&config, sink_device);
     app.main_loop()?;
     Ok(())
@@ -1,25 +1,39 @@
+use std::collections::HashMap;
+
 use evdev::uinput::VirtualDevice;
-use evdev::{AttributeSet, KeyCode, RelativeAxisCode};
+use evdev::{AttributeSet, EventType, InputEvent, KeyCode, RelativeAxisCode};
 
+use crate::config::{self, WheelOutput};
 use crate::errors::Error;
 use crate::sink_event::SinkEvent;
 
 pub struct SinkDevice {
     vdevice: VirtualDevice,
+    /// Keyboard keys are emitted from another device so as to keep `vdevice` detected as a mouse. It is created only if some device can emit keyboard keys.
+    keyboard_vdevice: Option<VirtualDevice>,
     name: String,
+    /// Keys which are held down by each source device.
+    held_keys: HashMap<String, Vec<u16>>,
 }
 
 impl SinkDevice {
-    pub fn create(name: &str) -> Result<Self, Error> {
+    pub fn create(name: &str, wheel_output: WheelOutput, keyboard: bool) -> Result<Self, Error> {
         let mut keys = AttributeSet::<KeyCode>::new();
         // Note: when keyboard keys are enabled, it is not detected as a mouse
-        for code in KeyCode::BTN_0.code()..=KeyCode::BTN_THUMBR.code() {
+        for code in config::BUTTON_CODES {
             keys.insert(KeyCode::new(code));
         }
 
         let mut rel_axes = AttributeSet::<RelativeAxisCode>::new();
-        for code in RelativeAxisCode::REL_X.0..=RelativeAxisCode::REL_HWHEEL_HI_RES.0 {
-            rel_axes.insert(RelativeAxisCode(code));
+        rel_axes.insert(RelativeAxisCode::REL_X);
+        rel_axes.insert(RelativeAxisCode::REL_Y);
+        if wheel_output.legacy() {
+            rel_axes.insert(RelativeAxisCode::REL_WHEEL);
+            rel_axes.insert(RelativeAxisCode::REL_HWHEEL);
+        }
+        if wheel_output.hi_res() {
+            rel_axes.insert(RelativeAxisCode::REL_WHEEL_HI_RES);
+            rel_axes.insert(RelativeAxisCode::REL_HWHEEL_HI_RES);
         }
 
         let vdevice = VirtualDevice::builder()?
@@ -27,18 +41,97 @@ impl SinkDevice {
             .with_keys(&keys)?
             .with_relative_axes(&rel_axes)?
             .build()?;
+
+        let keyboard_vdevice = if keyboard {
+            let mut keyboard_keys = AttributeSet::<KeyCode>::new();
+            for code in
+                (0..KeyCode::BTN_TRIGGER_HAPPY1.code()).filter(|x| config::is_keyboard_key(*x))
+            {
+                keyboard_keys.insert(KeyCode::new(code));
+            }
+            Some(
+                VirtualDevice::builder()?
+                    .name(&format!("{} keyboard", name))
+                    .with_keys(&keyboard_keys)?
+                    .build()?,
+            )
+        } else {
+            None
+        };
+
         Ok(Self {
             vdevice,
+            keyboard_vdevice,
             name: name.to_string(),
+            held_keys: HashMap::new(),
         })
     }
 
-    pub fn send_event(&mut self, event: &SinkEvent) -> Result<(), Error> {
-        self.vdevice.emit(event.as_ref())?;
+    pub fn send_event(&mut self, source: &str, event: &SinkEvent) -> Result<(), Error> {
+        let held = self.held_keys.entry(source.to_string()).or_default();
+        for x in event.as_ref() {
+            if x.event_type() != EventType::KEY {
+                continue;
+            }
+            held.retain(|code| *code != x.code());
+            if x.value() != 0 {
+                held.push(x.code());
+            }
+        }
+        self.emit(event.as_ref())
+    }
+
+    /// Releases keys held down by the source device.
+    pub fn release(&mut self, source: &str) -> Result<(), Error> {
+        let held = self.held_keys.remove(source).unwrap_or_default();
+        let events = held
+            .iter()
+            .filter(|code| !self.held_keys.values().any(|x| x.contains(code)))
+            .map(|code| InputEvent::new(EventType::KEY.0, *code, 0))
+            .collect::<Vec<_>>();
+        self.emit(&events)
+    }
+
+    /// Releases keys held down by all source devices.
+    pub fn release_all(&mut self) -> Result<(), Error> {
+        let sources = self.held_keys.keys().cloned().collect::<Vec<_>>();
+        for x in sources {
+            self.release(&x)?;
+        }
         Ok(())
     }
 
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Emits events in order, sending each run of keyboard key events to the keyboard device.
+    fn emit(&mut self, events: &[InputEvent]) -> Result<(), Error> {
+        let mut start = 0;
+        let mut keyboard = false;
+        for (i, x) in events.iter().enumerate() {
+            if x.event_type() == EventType::SYNCHRONIZATION {
+                continue;
+            }
+            let is_keyboard = x.event_type() == EventType::KEY && config::is_keyboard_key(x.code());
+            if is_keyboard != keyboard {
+                self.emit_to(keyboard, &events[start..i])?;
+                start = i;
+                keyboard = is_keyboard;
+            }
+        }
+        self.emit_to(keyboard, &events[start..])
+    }
+
+    fn emit_to(&mut self, keyboard: bool, events: &[InputEvent]) -> Result<(), Error> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        if !keyboard {
+            self.vdevice.emit(events)?;
+        } else if let Some(x) = &mut self.keyboard_vdevice {
+            x.emit(events)?;
+        }
+        Ok(())
+    }
 }
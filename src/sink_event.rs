@@ -1,15 +1,182 @@
-use evdev::{AbsoluteAxisCode, EventType, InputEvent, RelativeAxisCode};
+use std::collections::HashSet;
+
+use evdev::{
+    AbsoluteAxisCode, EventType, InputEvent, KeyCode, RelativeAxisCode, SynchronizationCode,
+};
 use input::event::PointerEvent;
 use input::event::pointer::{
-    Axis, ButtonState, PointerButtonEvent, PointerScrollEvent, PointerScrollWheelEvent,
+    Axis, ButtonState, PointerEventTrait, PointerScrollEvent, PointerScrollWheelEvent,
 };
 
 use crate::config;
 use crate::errors::{self, Error};
 
+use self::autoscroll::AutoscrollState;
+use self::axis_lock::AxisLockState;
+use self::button_mapping::ButtonMappingState;
+use self::chord::ChordState;
+use self::continuous_scroll::ContinuousScrollState;
+use self::custom_accel::CustomAccelState;
+use self::dead_zone::DeadZoneState;
+use self::debounce::DebounceState;
+use self::drag_lock::DragLockState;
+use self::horizontal_scroll::HorizontalScrollState;
+use self::kinetic_scroll::KineticScrollState;
+use self::layer::LayerState;
+use self::motion_remainder::MotionRemainderState;
+use self::precision::PrecisionState;
+use self::ratchet::RatchetState;
+use self::repeat::RepeatState;
+use self::scroll_acceleration::ScrollAccelerationState;
+use self::scroll_remainder::ScrollRemainderState;
+use self::smooth_scroll::SmoothScrollState;
+use self::wheel_mapping::WheelMappingState;
+use self::zoom::ZoomState;
+
+mod autoscroll;
+mod axis_lock;
+mod button_mapping;
+mod chord;
+mod continuous_scroll;
+mod custom_accel;
+mod dead_zone;
+mod debounce;
+mod drag_lock;
+mod held_modifier;
+mod horizontal_scroll;
+mod kinetic_scroll;
+mod layer;
+mod motion_remainder;
+mod precision;
+mod ratchet;
+mod repeat;
+mod scroll_acceleration;
+mod scroll_remainder;
+mod smooth_scroll;
+#[cfg(test)]
+mod test_util;
+mod wheel_mapping;
+mod zoom;
+
 #[derive(Debug)]
 pub struct SinkEvent(Vec<InputEvent>);
 
+/// Conversion state of a grabbed device.
+#[derive(Debug, Default)]
+pub struct DeviceState {
+    autoscroll: AutoscrollState,
+    axis_lock: AxisLockState,
+    button_mapping: ButtonMappingState,
+    chord: ChordState,
+    continuous_scroll: ContinuousScrollState,
+    custom_accel: CustomAccelState,
+    dead_zone: DeadZoneState,
+    debounce: DebounceState,
+    drag_lock: DragLockState,
+    /// Buttons which are physically held, before any of them is consumed or mapped.
+    held: HashSet<config::Button>,
+    horizontal_scroll: HorizontalScrollState,
+    kinetic_scroll: KineticScrollState,
+    layer: LayerState,
+    motion_remainder: MotionRemainderState,
+    /// Speed of motion scrolls, which is kept apart from the one of wheel scrolls.
+    motion_scroll_acceleration: ScrollAccelerationState,
+    precision: PrecisionState,
+    ratchet: RatchetState,
+    repeat: RepeatState,
+    scroll_remainder: ScrollRemainderState,
+    smooth_scroll: SmoothScrollState,
+    wheel_mapping: WheelMappingState,
+    wheel_scroll_acceleration: ScrollAccelerationState,
+    zoom: ZoomState,
+}
+
+/// Interval of the scroll events which timers emit, e.g. while a button is held or after motion scrolling stops.
+const TICK_USEC: u64 = 10_000;
+
+impl DeviceState {
+    /// Returns the time in microseconds when [`SinkEvent::from_timer`] should be called next.
+    pub fn deadline(&self) -> Option<u64> {
+        [
+            self.autoscroll.deadline(),
+            self.chord.deadline(),
+            self.continuous_scroll.deadline(),
+            self.kinetic_scroll.deadline(),
+            self.repeat.deadline(),
+            self.smooth_scroll.deadline(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    /// Returns the number of suppressed button bounces.
+    pub fn debounce_suppressed(&self) -> u64 {
+        self.debounce.suppressed()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ButtonEvent {
+    button: config::Button,
+    state: ButtonState,
+    time: u64,
+}
+
+/// Scroll values of each axis in hi-res units (120 per notch), `None` if the axis is not scrolled.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Scroll {
+    vertical: Option<f64>,
+    horizontal: Option<f64>,
+}
+
+impl Scroll {
+    fn from_event(ev: &impl PointerScrollEvent, v120: impl Fn(Axis) -> f64) -> Self {
+        let axis_value = |axis| ev.has_axis(axis).then(|| v120(axis));
+        Self {
+            vertical: axis_value(Axis::Vertical),
+            horizontal: axis_value(Axis::Horizontal),
+        }
+    }
+
+    /// Returns true if the scroll is the terminating event of motion scrolling, which has zero values.
+    fn is_stop(&self) -> bool {
+        let values = [self.vertical, self.horizontal];
+        values.iter().any(Option::is_some) && values.iter().flatten().all(|x| *x == 0.0)
+    }
+
+    /// Moves the vertical scroll to the horizontal axis, adding it to the horizontal scroll.
+    fn into_horizontal(self) -> Self {
+        let horizontal = match (self.vertical, self.horizontal) {
+            (Some(v), Some(h)) => Some(v + h),
+            (v, h) => v.or(h),
+        };
+        Self {
+            vertical: None,
+            horizontal,
+        }
+    }
+
+    /// Creates a scroll to the direction. `v120` should not be negative.
+    fn directed(direction: config::ScrollDirection, v120: f64) -> Self {
+        let v120 = match direction {
+            config::ScrollDirection::Up | config::ScrollDirection::Left => -v120,
+            config::ScrollDirection::Down | config::ScrollDirection::Right => v120,
+        };
+        let scroll_value = Some(v120);
+        match direction {
+            config::ScrollDirection::Up | config::ScrollDirection::Down => Self {
+                vertical: scroll_value,
+                horizontal: None,
+            },
+            config::ScrollDirection::Left | config::ScrollDirection::Right => Self {
+                vertical: None,
+                horizontal: scroll_value,
+            },
+        }
+    }
+}
+
 impl AsRef<Vec<InputEvent>> for SinkEvent {
     fn as_ref(&self) -> &Vec<InputEvent> {
         &self.0
@@ -20,26 +187,65 @@ impl SinkEvent {
     pub fn from_pointer_event(
         event: &PointerEvent,
         device_config: &config::Device,
+        state: &mut DeviceState,
     ) -> Result<Self, Error> {
+        // A new pointer event stops kinetic scrolling, except motion scrolls which start it and the legacy axis events accompanying them.
+        #[allow(deprecated)]
+        if !matches!(
+            event,
+            PointerEvent::ScrollFinger(_)
+                | PointerEvent::ScrollContinuous(_)
+                | PointerEvent::Axis(_)
+        ) {
+            state.kinetic_scroll.stop();
+        }
+
         match event {
-            PointerEvent::Motion(ev) => Ok(Self(vec![
-                new_relative_event(RelativeAxisCode::REL_X, ev.dx()),
-                new_relative_event(RelativeAxisCode::REL_Y, ev.dy()),
-            ])),
+            PointerEvent::Motion(ev) => {
+                let (dx, dy) = match &device_config.accel_profile {
+                    Some(config::AccelProfile::Custom(x)) => state.custom_accel.process(
+                        ev.dx_unaccelerated(),
+                        ev.dy_unaccelerated(),
+                        ev.time_usec(),
+                        x.motion(),
+                    ),
+                    _ => (ev.dx(), ev.dy()),
+                };
+                Ok(Self(convert_motion(
+                    dx,
+                    dy,
+                    ev.time_usec(),
+                    device_config,
+                    state,
+                )))
+            }
             PointerEvent::MotionAbsolute(ev) => Ok(Self(vec![
                 new_absolute_event(AbsoluteAxisCode::ABS_X, ev.absolute_x()),
                 new_absolute_event(AbsoluteAxisCode::ABS_Y, ev.absolute_y()),
             ])),
-            PointerEvent::Button(ev) => Ok(Self(convert_button(ev, device_config))),
-            PointerEvent::ScrollWheel(ev) => {
-                Ok(Self(convert_wheel_scroll_event(ev, device_config)))
+            PointerEvent::Button(ev) => {
+                let source = ButtonEvent {
+                    button: config::Button::from_code(ev.button() as u16),
+                    state: ev.button_state(),
+                    time: ev.time_usec(),
+                };
+                Ok(Self(convert_source_button(source, device_config, state)))
             }
-            PointerEvent::ScrollFinger(ev) => {
-                Ok(Self(convert_motion_scroll_event(ev, device_config)))
-            }
-            PointerEvent::ScrollContinuous(ev) => {
-                Ok(Self(convert_motion_scroll_event(ev, device_config)))
+            PointerEvent::ScrollWheel(ev) => {
+                Ok(Self(convert_wheel_scroll_event(ev, device_config, state)))
             }
+            PointerEvent::ScrollFinger(ev) => Ok(Self(convert_motion_scroll_event(
+                ev,
+                device_config,
+                state,
+                false,
+            ))),
+            PointerEvent::ScrollContinuous(ev) => Ok(Self(convert_motion_scroll_event(
+                ev,
+                device_config,
+                state,
+                true,
+            ))),
             #[allow(deprecated)]
             PointerEvent::Axis(_) => {
                 // We should ignore axis event when to handle scroll events.
@@ -52,6 +258,34 @@ impl SinkEvent {
             ))),
         }
     }
+
+    /// Converts events which are delayed until `now`.
+    pub fn from_timer(now: u64, device_config: &config::Device, state: &mut DeviceState) -> Self {
+        let buttons = state.chord.expire(now);
+        let mut events = convert_buttons(buttons, device_config, state);
+        for (release, press) in state.repeat.expire(now, device_config) {
+            push_button_event(&mut events, release);
+            push_button_event(&mut events, press);
+        }
+        for x in state.continuous_scroll.expire(now) {
+            let scroll = apply_scroll_factor(x, wheel_scroll_factor(device_config, state));
+            push_scroll_event(&mut events, scroll, device_config, state);
+        }
+        if let Some(x) = state.autoscroll.expire(now, device_config) {
+            let scroll = apply_scroll_factor(x, wheel_scroll_factor(device_config, state));
+            push_scroll_event(&mut events, scroll, device_config, state);
+        }
+        // The slices of smooth scrolls are already scaled as they come in.
+        if let Some(x) = state.smooth_scroll.expire(now, device_config) {
+            push_scroll_event(&mut events, x, device_config, state);
+        }
+        // Kinetic scrolls are already scaled as the motion scrolls which start them.
+        if let Some(x) = state.kinetic_scroll.expire(now, device_config) {
+            let scroll = state.ratchet.process(x, false, device_config);
+            push_scroll_event(&mut events, scroll, device_config, state);
+        }
+        Self(events)
+    }
 }
 
 fn new_relative_event(axis_type: RelativeAxisCode, value: f64) -> InputEvent {
@@ -62,6 +296,14 @@ fn new_absolute_event(axis_type: AbsoluteAxisCode, value: f64) -> InputEvent {
     InputEvent::new(EventType::ABSOLUTE.0, axis_type.0, value as i32)
 }
 
+fn new_syn_report_event() -> InputEvent {
+    InputEvent::new(
+        EventType::SYNCHRONIZATION.0,
+        SynchronizationCode::SYN_REPORT.0,
+        0,
+    )
+}
+
 fn new_button_event(button: u16, state: ButtonState) -> InputEvent {
     InputEvent::new(
         EventType::KEY.0,
@@ -73,58 +315,784 @@ fn new_button_event(button: u16, state: ButtonState) -> InputEvent {
     )
 }
 
+/// Applies the swap, factors and inversions of the axes to the scroll.
+fn apply_scroll_factor(scroll: Scroll, scroll_factor: &config::ScrollFactorPair) -> Scroll {
+    let scroll = if scroll_factor.swap_axes {
+        Scroll {
+            vertical: scroll.horizontal,
+            horizontal: scroll.vertical,
+        }
+    } else {
+        scroll
+    };
+    let sign = |invert| if invert { -1.0 } else { 1.0 };
+    Scroll {
+        vertical: scroll
+            .vertical
+            .map(|x| x * scroll_factor.vertical.value() * sign(scroll_factor.invert_vertical)),
+        horizontal: scroll
+            .horizontal
+            .map(|x| x * scroll_factor.horizontal.value() * sign(scroll_factor.invert_horizontal)),
+    }
+}
+
 fn dispatch_scroll_event(
-    ev: &impl PointerScrollEvent,
-    scroll_factor: &config::ScrollFactorPair,
-    scroll_value: impl Fn(Axis) -> (f64, f64),
+    scroll: Scroll,
+    wheel_output: config::WheelOutput,
+    remainder: &mut ScrollRemainderState,
 ) -> Vec<InputEvent> {
+    // Events of zero value are dropped by the kernel anyway, so only the others are emitted.
     let mut res = Vec::new();
-    if ev.has_axis(Axis::Vertical) {
-        let (v, v120) = scroll_value(Axis::Vertical);
-        res.push(new_relative_event(
-            RelativeAxisCode::REL_WHEEL,
-            -v * scroll_factor.vertical.value(),
-        ));
-        res.push(new_relative_event(
+    let mut push = |enabled: bool, axis, value: i32| {
+        if enabled && value != 0 {
+            res.push(new_relative_event(axis, value.into()));
+        }
+    };
+    if let Some(x) = scroll.vertical {
+        let (notches, v120) = remainder.vertical(-x);
+        push(wheel_output.legacy(), RelativeAxisCode::REL_WHEEL, notches);
+        push(
+            wheel_output.hi_res(),
             RelativeAxisCode::REL_WHEEL_HI_RES,
-            -v120 * scroll_factor.vertical.value(),
-        ));
-    }
-    if ev.has_axis(Axis::Horizontal) {
-        let (v, v120) = scroll_value(Axis::Horizontal);
-        res.push(new_relative_event(
-            RelativeAxisCode::REL_HWHEEL,
-            v * scroll_factor.horizontal.value(),
-        ));
-        res.push(new_relative_event(
+            v120,
+        );
+    }
+    if let Some(x) = scroll.horizontal {
+        let (notches, v120) = remainder.horizontal(x);
+        push(wheel_output.legacy(), RelativeAxisCode::REL_HWHEEL, notches);
+        push(
+            wheel_output.hi_res(),
             RelativeAxisCode::REL_HWHEEL_HI_RES,
-            v120 * scroll_factor.horizontal.value(),
-        ));
+            v120,
+        );
     }
     res
 }
 
+fn convert_motion(
+    dx: f64,
+    dy: f64,
+    time: u64,
+    cfg: &config::Device,
+    state: &mut DeviceState,
+) -> Vec<InputEvent> {
+    if state.autoscroll.process_motion(dx, dy, time) {
+        return Vec::new();
+    }
+    let factor = &cfg.motion_factor;
+    let precision = state.precision.factor(cfg);
+    let (dx, dy) = state.motion_remainder.process(
+        dx * factor.x.value() * precision,
+        dy * factor.y.value() * precision,
+    );
+    vec![
+        new_relative_event(RelativeAxisCode::REL_X, dx.into()),
+        new_relative_event(RelativeAxisCode::REL_Y, dy.into()),
+    ]
+}
+
 fn convert_motion_scroll_event(
-    ev: &impl PointerScrollEvent,
+    ev: &(impl PointerScrollEvent + PointerEventTrait),
     cfg: &config::Device,
+    state: &mut DeviceState,
+    accelerate: bool,
 ) -> Vec<InputEvent> {
-    dispatch_scroll_event(ev, &cfg.motion_scroll_factor, |axis| {
+    let scroll = Scroll::from_event(ev, |axis| {
         // The Linux input subsystem expects the REL_WHEEL_HI_RES value to be 8 times the standard REL_WHEEL value.
-        (ev.scroll_value(axis), ev.scroll_value(axis) * 8.0)
-    })
+        ev.scroll_value(axis) * 8.0
+    });
+    convert_motion_scroll(scroll, ev.time_usec(), cfg, state, accelerate)
+}
+
+fn convert_motion_scroll(
+    mut scroll: Scroll,
+    time: u64,
+    cfg: &config::Device,
+    state: &mut DeviceState,
+    accelerate: bool,
+) -> Vec<InputEvent> {
+    // The end of the gesture is decided before the stages filter the axes, which can leave only zero values in the middle of it.
+    let stop = scroll.is_stop();
+    scroll = state.dead_zone.process(scroll, stop, cfg);
+    scroll = state.axis_lock.process(scroll, stop, cfg);
+    let scroll_factor = motion_scroll_factor(cfg, state);
+    if accelerate {
+        scroll = state.motion_scroll_acceleration.process(
+            scroll,
+            time,
+            scroll_factor.acceleration.as_ref(),
+        );
+    }
+    // Scale before quantizing, so that the ratchet emits whole notches.
+    let scroll = apply_scroll_factor(scroll, scroll_factor);
+    state.kinetic_scroll.process(scroll, stop, time, cfg);
+    let scroll = state.ratchet.process(scroll, stop, cfg);
+    let mut res = Vec::new();
+    push_scroll_event(&mut res, scroll, cfg, state);
+    res
 }
 
 fn convert_wheel_scroll_event(
     ev: &PointerScrollWheelEvent,
     cfg: &config::Device,
+    state: &mut DeviceState,
+) -> Vec<InputEvent> {
+    let scroll = Scroll::from_event(ev, |axis| ev.scroll_value_v120(axis));
+    let (mut scroll, keys) = state
+        .wheel_mapping
+        .process(scroll, cfg, |x| state.held.contains(&x));
+    let mut res = Vec::new();
+    for x in keys {
+        push_key_event(&mut res, x.code(), ButtonState::Pressed);
+        push_key_event(&mut res, x.code(), ButtonState::Released);
+    }
+
+    let modified = state.horizontal_scroll.use_wheel();
+    let layer = state.layer.active(cfg);
+    if modified || layer.is_some_and(|x| x.horizontal_wheel) {
+        scroll = scroll.into_horizontal();
+    }
+    let scroll_factor = wheel_scroll_factor(cfg, state);
+    let scroll = state.wheel_scroll_acceleration.process(
+        scroll,
+        ev.time_usec(),
+        scroll_factor.acceleration.as_ref(),
+    );
+    // Scale before spreading, so that the slices use the factor of the time the notch came in.
+    let scroll = apply_scroll_factor(scroll, scroll_factor);
+    if cfg.smooth_scroll_ms.is_some() {
+        state.smooth_scroll.process(scroll, ev.time_usec());
+        return res;
+    }
+    push_scroll_event(&mut res, scroll, cfg, state);
+    res
+}
+
+fn wheel_scroll_factor<'a>(
+    cfg: &'a config::Device,
+    state: &DeviceState,
+) -> &'a config::ScrollFactorPair {
+    state
+        .layer
+        .active(cfg)
+        .and_then(|x| x.wheel_scroll_factor.as_ref())
+        .unwrap_or(&cfg.wheel_scroll_factor)
+}
+
+fn motion_scroll_factor<'a>(
+    cfg: &'a config::Device,
+    state: &DeviceState,
+) -> &'a config::ScrollFactorPair {
+    state
+        .layer
+        .active(cfg)
+        .and_then(|x| x.motion_scroll_factor.as_ref())
+        .unwrap_or(&cfg.motion_scroll_factor)
+}
+
+fn convert_source_button(
+    source: ButtonEvent,
+    cfg: &config::Device,
+    state: &mut DeviceState,
+) -> Vec<InputEvent> {
+    if !state.debounce.process(source, cfg) {
+        return Vec::new();
+    }
+    match source.state {
+        ButtonState::Pressed => state.held.insert(source.button),
+        ButtonState::Released => state.held.remove(&source.button),
+    };
+    // Autoscroll goes first, so that any click ends it, including one of the precision button.
+    if state.autoscroll.process_button(source, cfg) || state.precision.process_button(source, cfg) {
+        return Vec::new();
+    }
+    convert_button(source, cfg, state)
+}
+
+fn convert_button(
+    source: ButtonEvent,
+    cfg: &config::Device,
+    state: &mut DeviceState,
+) -> Vec<InputEvent> {
+    let mut res = Vec::new();
+    // Config validation keeps a button from being a modifier of more than one kind. Otherwise zoom would take precedence over horizontal scroll, and that over wheel mapping.
+    let (sources, release_ctrl) = state.zoom.process(source, cfg);
+    if release_ctrl {
+        // The rest of smooth scrolls is emitted while Ctrl is still held, so that it zooms too. Kinetic scrolling is already stopped by the button event.
+        if let Some(x) = state.smooth_scroll.flush() {
+            res.extend(dispatch_scroll_event(
+                x,
+                cfg.wheel_output,
+                &mut state.scroll_remainder,
+            ));
+        }
+        push_key_event(
+            &mut res,
+            KeyCode::KEY_LEFTCTRL.code(),
+            ButtonState::Released,
+        );
+    }
+    let buttons = sources
+        .into_iter()
+        .flat_map(|x| state.horizontal_scroll.process(x, cfg))
+        .flat_map(|x| state.wheel_mapping.process_button(x, cfg))
+        .flat_map(|x| state.chord.process(x, cfg))
+        .collect();
+    res.extend(convert_buttons(buttons, cfg, state));
+    res
+}
+
+fn convert_buttons(
+    buttons: Vec<ButtonEvent>,
+    cfg: &config::Device,
+    state: &mut DeviceState,
 ) -> Vec<InputEvent> {
-    dispatch_scroll_event(ev, &cfg.wheel_scroll_factor, |axis| {
-        (ev.scroll_value(axis), ev.scroll_value_v120(axis))
-    })
+    let mut res = Vec::new();
+    for x in buttons {
+        if state.layer.process(x, cfg) {
+            continue;
+        }
+        let target = state
+            .button_mapping
+            .process(x, cfg, state.layer.active(cfg));
+        let mapped = match target {
+            config::ButtonTarget::Button(button) => Some(ButtonEvent { button, ..x }),
+            _ => None,
+        };
+        state.repeat.process(x, mapped, cfg);
+        if let Some(mapped) = mapped
+            && state.drag_lock.process(mapped, cfg)
+        {
+            push_button_event(&mut res, mapped);
+        }
+
+        match target {
+            config::ButtonTarget::Wheel(direction) => {
+                if x.state == ButtonState::Pressed {
+                    let scroll = Scroll::directed(direction, 120.0);
+                    let scroll = apply_scroll_factor(scroll, wheel_scroll_factor(cfg, state));
+                    push_scroll_event(&mut res, scroll, cfg, state);
+                }
+            }
+            config::ButtonTarget::Scroll { direction, rate } => {
+                state.continuous_scroll.process(x, direction, rate);
+            }
+            config::ButtonTarget::Button(_) | config::ButtonTarget::None => {}
+        }
+
+        // A one-shot layer is deactivated after the press is handled, so that the press can use its scroll factor.
+        if x.state == ButtonState::Pressed {
+            state.layer.finish_one_shot(cfg);
+        }
+    }
+    res
+}
+
+/// Pushes the wheel events of the scroll, pressing Ctrl before the first of them while the zoom modifier is held.
+fn push_scroll_event(
+    events: &mut Vec<InputEvent>,
+    scroll: Scroll,
+    cfg: &config::Device,
+    state: &mut DeviceState,
+) {
+    let wheel_events = dispatch_scroll_event(scroll, cfg.wheel_output, &mut state.scroll_remainder);
+    if wheel_events.is_empty() {
+        return;
+    }
+    if state.zoom.begin_scroll() {
+        push_key_event(events, KeyCode::KEY_LEFTCTRL.code(), ButtonState::Pressed);
+    }
+    events.extend(wheel_events);
 }
 
-fn convert_button(ev: &PointerButtonEvent, cfg: &config::Device) -> Vec<InputEvent> {
-    let source_button = config::Button::from_code(ev.button() as u16);
-    let button = cfg.map_button(source_button);
-    vec![new_button_event(button.code(), ev.button_state())]
+fn push_button_event(events: &mut Vec<InputEvent>, ev: ButtonEvent) {
+    push_key_event(events, ev.button.code(), ev.state);
+}
+
+fn push_key_event(events: &mut Vec<InputEvent>, code: u16, state: ButtonState) {
+    // A press and release of the same key in one frame would be lost, so separate each key event.
+    if !events.is_empty() {
+        events.push(new_syn_report_event());
+    }
+    events.push(new_button_event(code, state));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink_event::test_util::{button, button_event, device_config, horizontal, vertical};
+
+    fn key(code: KeyCode, value: i32) -> (EventType, u16, i32) {
+        (EventType::KEY, code.code(), value)
+    }
+
+    fn rel(axis: RelativeAxisCode, value: i32) -> (EventType, u16, i32) {
+        (EventType::RELATIVE, axis.0, value)
+    }
+
+    fn syn() -> (EventType, u16, i32) {
+        (
+            EventType::SYNCHRONIZATION,
+            SynchronizationCode::SYN_REPORT.0,
+            0,
+        )
+    }
+
+    fn events(events: &[InputEvent]) -> Vec<(EventType, u16, i32)> {
+        events
+            .iter()
+            .map(|x| (x.event_type(), x.code(), x.value()))
+            .collect()
+    }
+
+    #[test]
+    fn test_into_horizontal() {
+        assert_eq!(
+            vertical(120.0).into_horizontal(),
+            Scroll {
+                vertical: None,
+                horizontal: Some(120.0),
+            }
+        );
+        let tilted = Scroll {
+            vertical: None,
+            horizontal: Some(-120.0),
+        };
+        assert_eq!(tilted.into_horizontal(), tilted);
+        let both = Scroll {
+            vertical: Some(120.0),
+            horizontal: Some(-240.0),
+        };
+        assert_eq!(both.into_horizontal(), tilted);
+    }
+
+    fn dispatch_with_factor(scroll: Scroll, factor: &str) -> Vec<(EventType, u16, i32)> {
+        let factor = serde_yaml::from_str(factor).unwrap();
+        events(&dispatch_scroll_event(
+            apply_scroll_factor(scroll, &factor),
+            config::WheelOutput::Both,
+            &mut ScrollRemainderState::default(),
+        ))
+    }
+
+    #[test]
+    fn test_dispatch_swap_axes() {
+        // Scrolling down is emitted as scrolling right, and scrolling right as scrolling down, which is negative on the vertical axis.
+        assert_eq!(
+            dispatch_with_factor(vertical(120.0), "{swap_axes: true}"),
+            vec![
+                rel(RelativeAxisCode::REL_HWHEEL, 1),
+                rel(RelativeAxisCode::REL_HWHEEL_HI_RES, 120)
+            ]
+        );
+        assert_eq!(
+            dispatch_with_factor(horizontal(120.0), "{swap_axes: true}"),
+            vec![
+                rel(RelativeAxisCode::REL_WHEEL, -1),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, -120)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dispatch_invert() {
+        assert_eq!(
+            dispatch_with_factor(vertical(120.0), "{invert_vertical: true}"),
+            vec![
+                rel(RelativeAxisCode::REL_WHEEL, 1),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, 120)
+            ]
+        );
+        assert_eq!(
+            dispatch_with_factor(horizontal(120.0), "{invert_horizontal: true}"),
+            vec![
+                rel(RelativeAxisCode::REL_HWHEEL, -1),
+                rel(RelativeAxisCode::REL_HWHEEL_HI_RES, -120)
+            ]
+        );
+        // Inverting one axis does not affect the other.
+        assert_eq!(
+            dispatch_with_factor(horizontal(120.0), "{invert_vertical: true}"),
+            vec![
+                rel(RelativeAxisCode::REL_HWHEEL, 1),
+                rel(RelativeAxisCode::REL_HWHEEL_HI_RES, 120)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dispatch_swap_and_invert() {
+        // The factor and inversion of the vertical axis apply to the horizontal scroll swapped into it.
+        let factor = "{swap_axes: true, vertical: 2, invert_vertical: true}";
+        assert_eq!(
+            dispatch_with_factor(horizontal(120.0), factor),
+            vec![
+                rel(RelativeAxisCode::REL_WHEEL, 2),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, 240)
+            ]
+        );
+        assert_eq!(
+            dispatch_with_factor(vertical(120.0), factor),
+            vec![
+                rel(RelativeAxisCode::REL_HWHEEL, 1),
+                rel(RelativeAxisCode::REL_HWHEEL_HI_RES, 120)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dispatch_legacy_per_notch() {
+        let mut remainder = ScrollRemainderState::default();
+        let mut dispatch = |x| {
+            events(&dispatch_scroll_event(
+                x,
+                config::WheelOutput::Both,
+                &mut remainder,
+            ))
+        };
+
+        // A motion scroll value of 7.5 is 60 hi-res units.
+        assert_eq!(
+            dispatch(vertical(7.5 * 8.0)),
+            vec![rel(RelativeAxisCode::REL_WHEEL_HI_RES, -60)]
+        );
+        assert_eq!(
+            dispatch(vertical(7.5 * 8.0)),
+            vec![
+                rel(RelativeAxisCode::REL_WHEEL, -1),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, -60)
+            ]
+        );
+        assert_eq!(dispatch(vertical(0.0)), vec![]);
+    }
+
+    #[test]
+    fn test_scroll_acceleration_per_source() {
+        let cfg = device_config(
+            r#"
+            motion_scroll_factor:
+              acceleration: [[5, 1], [25, 3]]
+            "#,
+        );
+        let mut state = DeviceState::default();
+        let acceleration = cfg.motion_scroll_factor.acceleration.as_ref();
+
+        assert_eq!(
+            events(&convert_motion_scroll(
+                vertical(120.0),
+                0,
+                &cfg,
+                &mut state,
+                true
+            )),
+            vec![
+                rel(RelativeAxisCode::REL_WHEEL, -1),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, -120)
+            ]
+        );
+        // A wheel scroll in between does not change the speed of motion scrolls.
+        state
+            .wheel_scroll_acceleration
+            .process(vertical(120.0), 190_000, acceleration);
+        // 5 notches per second
+        assert_eq!(
+            events(&convert_motion_scroll(
+                vertical(120.0),
+                200_000,
+                &cfg,
+                &mut state,
+                true
+            )),
+            vec![
+                rel(RelativeAxisCode::REL_WHEEL, -1),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, -120)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ratchet_with_factor() {
+        let cfg = device_config(
+            r#"
+            motion_scroll_factor:
+              vertical: 0.5
+            motion_scroll_ratchet:
+              distance: 50
+            "#,
+        );
+        let mut state = DeviceState::default();
+
+        // 80 units of travel are scaled to 40, within a notch.
+        assert_eq!(
+            events(&convert_motion_scroll(
+                vertical(80.0),
+                0,
+                &cfg,
+                &mut state,
+                false
+            )),
+            vec![]
+        );
+        // 60 more units reach a whole notch, and the factor does not apply to it again.
+        assert_eq!(
+            events(&convert_motion_scroll(
+                vertical(60.0),
+                10_000,
+                &cfg,
+                &mut state,
+                false
+            )),
+            vec![
+                rel(RelativeAxisCode::REL_WHEEL, -1),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, -120)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_axis_lock_within_gesture() {
+        let cfg = device_config(
+            r#"
+            axis_lock:
+              distance: 30
+              diagonal_ratio: 0.5
+            motion_scroll_ratchet:
+              distance: 100
+            kinetic_scroll:
+              friction: 10
+              min_velocity: 500
+            "#,
+        );
+        let mut state = DeviceState::default();
+        let scroll = |vertical, horizontal| Scroll {
+            vertical: Some(vertical),
+            horizontal: Some(horizontal),
+        };
+
+        // Horizontal jitter is locked out without ending the gesture.
+        let mut res = Vec::new();
+        for (i, x) in [60.0, 0.0, 60.0, 0.0, 60.0, 0.0, 60.0]
+            .into_iter()
+            .enumerate()
+        {
+            let time = i as u64 * 10_000;
+            res.extend(events(&convert_motion_scroll(
+                scroll(x, 5.0),
+                time,
+                &cfg,
+                &mut state,
+                false,
+            )));
+            assert_eq!(state.kinetic_scroll.deadline(), None);
+        }
+        assert_eq!(
+            res,
+            vec![
+                rel(RelativeAxisCode::REL_WHEEL, -1),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, -120),
+                rel(RelativeAxisCode::REL_WHEEL, -1),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, -120),
+            ]
+        );
+
+        convert_motion_scroll(scroll(0.0, 0.0), 70_000, &cfg, &mut state, false);
+        assert_eq!(state.kinetic_scroll.deadline(), Some(80_000));
+    }
+
+    #[test]
+    fn test_smooth_slices_unscaled() {
+        let cfg = device_config(
+            r#"
+            smooth_scroll_ms: 50
+            wheel_scroll_factor:
+              vertical: 2
+            "#,
+        );
+        let mut state = DeviceState::default();
+
+        // The notch is scaled when it comes in.
+        state.smooth_scroll.process(vertical(240.0), 0);
+        let mut total = 0;
+        for now in (10_000..=50_000).step_by(10_000) {
+            for (_, code, value) in events(SinkEvent::from_timer(now, &cfg, &mut state).as_ref()) {
+                if code == RelativeAxisCode::REL_WHEEL_HI_RES.0 {
+                    total += value;
+                }
+            }
+        }
+        assert_eq!(total, -240);
+    }
+
+    #[test]
+    fn test_zoom_only_when_scrolled() {
+        let cfg = device_config(
+            r#"
+            zoom_modifier: BTN_EXTRA
+            "#,
+        );
+        let mut state = DeviceState::default();
+
+        // A scroll which emits nothing, e.g. swallowed by the dead zone, does not use the modifier.
+        let press = button_event(KeyCode::BTN_EXTRA, ButtonState::Pressed, 0);
+        assert!(convert_button(press, &cfg, &mut state).is_empty());
+        let mut res = Vec::new();
+        push_scroll_event(&mut res, vertical(0.0), &cfg, &mut state);
+        push_scroll_event(&mut res, Scroll::default(), &cfg, &mut state);
+        assert_eq!(events(&res), vec![]);
+        let release = button_event(KeyCode::BTN_EXTRA, ButtonState::Released, 10_000);
+        assert_eq!(
+            events(&convert_button(release, &cfg, &mut state)),
+            vec![
+                key(KeyCode::BTN_EXTRA, 1),
+                syn(),
+                key(KeyCode::BTN_EXTRA, 0)
+            ]
+        );
+
+        let press = button_event(KeyCode::BTN_EXTRA, ButtonState::Pressed, 20_000);
+        assert!(convert_button(press, &cfg, &mut state).is_empty());
+        let mut res = Vec::new();
+        push_scroll_event(&mut res, vertical(120.0), &cfg, &mut state);
+        assert_eq!(
+            events(&res),
+            vec![
+                key(KeyCode::KEY_LEFTCTRL, 1),
+                rel(RelativeAxisCode::REL_WHEEL, -1),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, -120)
+            ]
+        );
+        let release = button_event(KeyCode::BTN_EXTRA, ButtonState::Released, 30_000);
+        assert_eq!(
+            events(&convert_button(release, &cfg, &mut state)),
+            vec![key(KeyCode::KEY_LEFTCTRL, 0)]
+        );
+    }
+
+    #[test]
+    fn test_zoom_timer_scrolls() {
+        let cfg = device_config(
+            r#"
+            zoom_modifier: BTN_EXTRA
+            smooth_scroll_ms: 50
+            "#,
+        );
+        let mut state = DeviceState::default();
+
+        let press = button_event(KeyCode::BTN_EXTRA, ButtonState::Pressed, 0);
+        assert!(convert_button(press, &cfg, &mut state).is_empty());
+        state.smooth_scroll.process(vertical(120.0), 0);
+        let res = SinkEvent::from_timer(10_000, &cfg, &mut state);
+        assert_eq!(
+            events(res.as_ref()),
+            vec![
+                key(KeyCode::KEY_LEFTCTRL, 1),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, -58)
+            ]
+        );
+
+        // The rest is emitted before Ctrl is released.
+        let release = button_event(KeyCode::BTN_EXTRA, ButtonState::Released, 15_000);
+        assert_eq!(
+            events(&convert_button(release, &cfg, &mut state)),
+            vec![
+                rel(RelativeAxisCode::REL_WHEEL, -1),
+                rel(RelativeAxisCode::REL_WHEEL_HI_RES, -62),
+                syn(),
+                key(KeyCode::KEY_LEFTCTRL, 0)
+            ]
+        );
+        assert_eq!(state.deadline(), None);
+    }
+
+    #[test]
+    fn test_motion_factor() {
+        let cfg = device_config(
+            r#"
+            motion_factor:
+              x: 0.25
+              y: 1.5
+            "#,
+        );
+        let mut state = DeviceState::default();
+
+        let (mut x, mut y) = (0, 0);
+        for _ in 0..10 {
+            for (_, code, value) in events(&convert_motion(-1.0, 1.0, 0, &cfg, &mut state)) {
+                match RelativeAxisCode(code) {
+                    RelativeAxisCode::REL_X => x += value,
+                    RelativeAxisCode::REL_Y => y += value,
+                    _ => unreachable!(),
+                }
+            }
+        }
+        assert_eq!((x, y), (-2, 15));
+    }
+
+    #[test]
+    fn test_held_consumed_button() {
+        let cfg = device_config(
+            r#"
+            layers:
+              layer:
+                button: BTN_SIDE
+            "#,
+        );
+        let mut state = DeviceState::default();
+        let side = button(KeyCode::BTN_SIDE);
+
+        let press = button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0);
+        assert!(convert_source_button(press, &cfg, &mut state).is_empty());
+        assert!(state.held.contains(&side));
+        let release = button_event(KeyCode::BTN_SIDE, ButtonState::Released, 10_000);
+        assert!(convert_source_button(release, &cfg, &mut state).is_empty());
+        assert!(!state.held.contains(&side));
+    }
+
+    #[test]
+    fn test_precision_click_ends_autoscroll() {
+        let cfg = device_config(
+            r#"
+            autoscroll:
+              button: BTN_MIDDLE
+            precision_button: BTN_SIDE
+            precision_button_lock: true
+            "#,
+        );
+        let mut state = DeviceState::default();
+
+        for (code, time) in [(KeyCode::BTN_MIDDLE, 0), (KeyCode::BTN_SIDE, 100_000)] {
+            let press = button_event(code, ButtonState::Pressed, time);
+            assert!(convert_source_button(press, &cfg, &mut state).is_empty());
+            let release = button_event(code, ButtonState::Released, time + 10_000);
+            assert!(convert_source_button(release, &cfg, &mut state).is_empty());
+        }
+        assert_eq!(state.precision.factor(&cfg), 1.0);
+        assert_eq!(
+            events(&convert_motion(0.0, 20.0, 120_000, &cfg, &mut state)),
+            vec![
+                rel(RelativeAxisCode::REL_X, 0),
+                rel(RelativeAxisCode::REL_Y, 20)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chord_click_in_separate_frames() {
+        let cfg = device_config(
+            r#"
+            chords:
+              - buttons: [BTN_LEFT, BTN_RIGHT]
+                target: BTN_MIDDLE
+            "#,
+        );
+        let mut state = DeviceState::default();
+
+        let press = button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, 0);
+        assert!(convert_button(press, &cfg, &mut state).is_empty());
+        let release = button_event(KeyCode::BTN_LEFT, ButtonState::Released, 10_000);
+        assert_eq!(
+            events(&convert_button(release, &cfg, &mut state)),
+            vec![key(KeyCode::BTN_LEFT, 1), syn(), key(KeyCode::BTN_LEFT, 0)]
+        );
+    }
 }
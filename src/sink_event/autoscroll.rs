@@ -0,0 +1,190 @@
+use input::event::pointer::ButtonState;
+
+use crate::config::{self, Button};
+
+use super::{ButtonEvent, Scroll, TICK_USEC};
+
+/// Scrolls by the distance of the pointer from the anchor, from a click of the autoscroll button until the next click.
+#[derive(Debug, Default)]
+pub struct AutoscrollState {
+    active: Option<ActiveAutoscroll>,
+    /// Buttons whose releases are dropped, as their presses started or stopped autoscroll.
+    suppressed: Vec<Button>,
+}
+
+#[derive(Debug)]
+struct ActiveAutoscroll {
+    last: u64,
+    dead_zone: f64,
+    /// Distance of the pointer from the anchor.
+    x: f64,
+    y: f64,
+}
+
+impl AutoscrollState {
+    pub fn deadline(&self) -> Option<u64> {
+        self.active
+            .as_ref()
+            .filter(|x| !x.in_dead_zone())
+            .map(|x| x.last + TICK_USEC)
+    }
+
+    /// Returns true if the button event is consumed by autoscroll.
+    pub fn process_button(&mut self, ev: ButtonEvent, cfg: &config::Device) -> bool {
+        let autoscroll = match &cfg.autoscroll {
+            Some(x) => x,
+            None => return false,
+        };
+        match ev.state {
+            ButtonState::Pressed => {
+                if self.active.take().is_none() {
+                    if ev.button != autoscroll.button {
+                        return false;
+                    }
+                    self.active = Some(ActiveAutoscroll {
+                        last: ev.time,
+                        dead_zone: autoscroll.dead_zone,
+                        x: 0.0,
+                        y: 0.0,
+                    });
+                }
+                self.suppressed.push(ev.button);
+                true
+            }
+            ButtonState::Released => {
+                let len = self.suppressed.len();
+                self.suppressed.retain(|x| *x != ev.button);
+                self.suppressed.len() != len
+            }
+        }
+    }
+
+    /// Returns true if the pointer motion is consumed by autoscroll.
+    pub fn process_motion(&mut self, dx: f64, dy: f64, time: u64) -> bool {
+        match &mut self.active {
+            Some(x) => {
+                // Scrolling starts when the pointer leaves the dead zone, not when it entered it.
+                if x.in_dead_zone() {
+                    x.last = time;
+                }
+                x.x += dx;
+                x.y += dy;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the scroll which is due until `now`.
+    pub fn expire(&mut self, now: u64, cfg: &config::Device) -> Option<Scroll> {
+        let autoscroll = cfg.autoscroll.as_ref()?;
+        let active = self.active.as_mut()?;
+        if active.in_dead_zone() || active.last + TICK_USEC > now {
+            return None;
+        }
+
+        let elapsed = (now - active.last) as f64 / 1_000_000.0;
+        active.last = now;
+        let v120 = |distance: f64| {
+            let beyond = (distance.abs() - active.dead_zone).max(0.0);
+            let v120 = distance.signum() * beyond * autoscroll.speed * elapsed * 120.0;
+            (v120 != 0.0).then_some(v120)
+        };
+        let scroll = Scroll {
+            vertical: v120(active.y),
+            horizontal: v120(active.x),
+        };
+        (scroll != Scroll::default()).then_some(scroll)
+    }
+}
+
+impl ActiveAutoscroll {
+    fn in_dead_zone(&self) -> bool {
+        self.x.abs() <= self.dead_zone && self.y.abs() <= self.dead_zone
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+    use crate::sink_event::test_util::{button_event, device_config};
+
+    const CONFIG: &str = r#"
+        autoscroll:
+          button: BTN_MIDDLE
+          speed: 0.5
+          dead_zone: 10
+        "#;
+
+    #[test]
+    fn test_autoscroll() {
+        let cfg = device_config(CONFIG);
+        let mut state = AutoscrollState::default();
+
+        assert!(!state.process_motion(5.0, 5.0, 0));
+        let press = button_event(KeyCode::BTN_MIDDLE, ButtonState::Pressed, 0);
+        assert!(state.process_button(press, &cfg));
+        let release = button_event(KeyCode::BTN_MIDDLE, ButtonState::Released, 50_000);
+        assert!(state.process_button(release, &cfg));
+        assert_eq!(state.deadline(), None);
+
+        // Within the dead zone
+        assert!(state.process_motion(2.0, 8.0, 60_000));
+        assert_eq!(state.expire(100_000, &cfg), None);
+        assert_eq!(state.deadline(), None);
+
+        assert!(state.process_motion(0.0, 32.0, 110_000));
+        assert_eq!(state.deadline(), Some(120_000));
+        // 30 units beyond the dead zone at 0.5 notches per second per unit, for the time since the pointer left it
+        assert_eq!(
+            state.expire(130_000, &cfg),
+            Some(Scroll {
+                vertical: Some(36.0),
+                horizontal: None,
+            })
+        );
+
+        assert!(state.process_motion(-20.0, -80.0, 135_000));
+        let scroll = state.expire(140_000, &cfg).unwrap();
+        assert!((scroll.vertical.unwrap() + 18.0).abs() < 1e-9);
+        assert!((scroll.horizontal.unwrap() + 4.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stop_by_click() {
+        let cfg = device_config(CONFIG);
+        let mut state = AutoscrollState::default();
+
+        let press = button_event(KeyCode::BTN_MIDDLE, ButtonState::Pressed, 0);
+        state.process_button(press, &cfg);
+        let release = button_event(KeyCode::BTN_MIDDLE, ButtonState::Released, 0);
+        state.process_button(release, &cfg);
+
+        let press = button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, 100_000);
+        assert!(state.process_button(press, &cfg));
+        assert_eq!(state.deadline(), None);
+        assert!(!state.process_motion(1.0, 1.0, 100_000));
+        let release = button_event(KeyCode::BTN_LEFT, ButtonState::Released, 150_000);
+        assert!(state.process_button(release, &cfg));
+
+        let press = button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, 200_000);
+        assert!(!state.process_button(press, &cfg));
+    }
+
+    #[test]
+    fn test_back_into_dead_zone() {
+        let cfg = device_config(CONFIG);
+        let mut state = AutoscrollState::default();
+
+        let press = button_event(KeyCode::BTN_MIDDLE, ButtonState::Pressed, 0);
+        state.process_button(press, &cfg);
+        assert!(state.process_motion(0.0, 20.0, 0));
+        assert_eq!(state.deadline(), Some(10_000));
+
+        assert!(state.process_motion(0.0, -15.0, 5_000));
+        assert_eq!(state.deadline(), None);
+        assert_eq!(state.expire(10_000, &cfg), None);
+    }
+}
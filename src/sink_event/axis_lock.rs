@@ -0,0 +1,135 @@
+use crate::config;
+
+use super::Scroll;
+
+/// Locks motion scroll gestures to their dominant axis.
+#[derive(Debug, Default)]
+pub struct AxisLockState {
+    /// Scrolled distances of the current gesture.
+    vertical: f64,
+    horizontal: f64,
+    lock: Option<Lock>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Lock {
+    Vertical,
+    Horizontal,
+    Both,
+}
+
+impl AxisLockState {
+    /// Returns the scroll with the locked axis removed.
+    pub fn process(&mut self, scroll: Scroll, stop: bool, cfg: &config::Device) -> Scroll {
+        let axis_lock = match &cfg.axis_lock {
+            Some(x) => x,
+            None => return scroll,
+        };
+        if stop {
+            *self = Self::default();
+            return scroll;
+        }
+
+        let lock = match self.lock {
+            Some(x) => x,
+            None => {
+                self.vertical += scroll.vertical.unwrap_or(0.0).abs();
+                self.horizontal += scroll.horizontal.unwrap_or(0.0).abs();
+                let (dominant, major, minor) = if self.vertical >= self.horizontal {
+                    (Lock::Vertical, self.vertical, self.horizontal)
+                } else {
+                    (Lock::Horizontal, self.horizontal, self.vertical)
+                };
+                if major < axis_lock.distance {
+                    // Scroll only the dominant axis until it is picked.
+                    dominant
+                } else if minor >= major * axis_lock.diagonal_ratio {
+                    *self.lock.insert(Lock::Both)
+                } else {
+                    *self.lock.insert(dominant)
+                }
+            }
+        };
+        match lock {
+            Lock::Vertical => Scroll {
+                horizontal: None,
+                ..scroll
+            },
+            Lock::Horizontal => Scroll {
+                vertical: None,
+                ..scroll
+            },
+            Lock::Both => scroll,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink_event::test_util::{device_config, horizontal, scroll, vertical};
+
+    const CONFIG: &str = r#"
+        axis_lock:
+          distance: 30
+          diagonal_ratio: 0.5
+        "#;
+
+    #[test]
+    fn test_lock() {
+        let cfg = device_config(CONFIG);
+        let mut state = AxisLockState::default();
+
+        assert_eq!(
+            state.process(scroll(-10.0, 4.0), false, &cfg),
+            vertical(-10.0)
+        );
+        assert_eq!(
+            state.process(scroll(-25.0, 5.0), false, &cfg),
+            vertical(-25.0)
+        );
+        // The horizontal axis is suppressed until the gesture ends.
+        assert_eq!(
+            state.process(scroll(-1.0, 20.0), false, &cfg),
+            vertical(-1.0)
+        );
+        assert_eq!(
+            state.process(scroll(0.0, 0.0), true, &cfg),
+            scroll(0.0, 0.0)
+        );
+        assert_eq!(
+            state.process(scroll(2.0, 40.0), false, &cfg),
+            horizontal(40.0)
+        );
+    }
+
+    #[test]
+    fn test_diagonal() {
+        let cfg = device_config(CONFIG);
+        let mut state = AxisLockState::default();
+
+        assert_eq!(
+            state.process(scroll(20.0, 15.0), false, &cfg),
+            vertical(20.0)
+        );
+        assert_eq!(
+            state.process(scroll(20.0, 15.0), false, &cfg),
+            scroll(20.0, 15.0)
+        );
+        assert_eq!(
+            state.process(scroll(1.0, 30.0), false, &cfg),
+            scroll(1.0, 30.0)
+        );
+    }
+
+    #[test]
+    fn test_disabled() {
+        let cfg = device_config("{}");
+        let mut state = AxisLockState::default();
+
+        assert_eq!(
+            state.process(scroll(20.0, 15.0), false, &cfg),
+            scroll(20.0, 15.0)
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use input::event::pointer::ButtonState;
+
+use crate::config::{self, Button, ButtonTarget, Layer};
+
+use super::ButtonEvent;
+
+/// Maps buttons, remembering the target of each press so that its release goes to the same target.
+#[derive(Debug, Default)]
+pub struct ButtonMappingState {
+    pressed: HashMap<Button, ButtonTarget>,
+}
+
+impl ButtonMappingState {
+    pub fn process(
+        &mut self,
+        ev: ButtonEvent,
+        cfg: &config::Device,
+        layer: Option<&Layer>,
+    ) -> ButtonTarget {
+        match ev.state {
+            ButtonState::Pressed => {
+                let target = cfg.map_button(ev.button, layer);
+                self.pressed.insert(ev.button, target);
+                target
+            }
+            ButtonState::Released => self
+                .pressed
+                .remove(&ev.button)
+                .unwrap_or_else(|| cfg.map_button(ev.button, layer)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+    use crate::sink_event::test_util::{button, button_event, device_config};
+
+    const CONFIG: &str = r#"
+        button_mapping:
+          BTN_SIDE: BTN_MIDDLE
+          BTN_EXTRA: none
+        disabled_buttons:
+          - BTN_TASK
+        layers:
+          layer:
+            button: BTN_FORWARD
+            button_mapping:
+              BTN_SIDE: BTN_BACK
+        "#;
+
+    #[test]
+    fn test_mapped() {
+        let cfg = device_config(CONFIG);
+        let mut state = ButtonMappingState::default();
+
+        let side = KeyCode::BTN_SIDE;
+        let middle = ButtonTarget::Button(button(KeyCode::BTN_MIDDLE));
+        assert_eq!(
+            state.process(button_event(side, ButtonState::Pressed, 0), &cfg, None),
+            middle
+        );
+        assert_eq!(
+            state.process(button_event(side, ButtonState::Released, 0), &cfg, None),
+            middle
+        );
+
+        let left = KeyCode::BTN_LEFT;
+        assert_eq!(
+            state.process(button_event(left, ButtonState::Pressed, 0), &cfg, None),
+            ButtonTarget::Button(button(left))
+        );
+    }
+
+    #[test]
+    fn test_dropped() {
+        let cfg = device_config(CONFIG);
+        let mut state = ButtonMappingState::default();
+
+        for code in [KeyCode::BTN_EXTRA, KeyCode::BTN_TASK] {
+            assert_eq!(
+                state.process(button_event(code, ButtonState::Pressed, 0), &cfg, None),
+                ButtonTarget::None
+            );
+            assert_eq!(
+                state.process(button_event(code, ButtonState::Released, 0), &cfg, None),
+                ButtonTarget::None
+            );
+        }
+    }
+
+    #[test]
+    fn test_release_follows_press() {
+        let cfg = device_config(CONFIG);
+        let mut state = ButtonMappingState::default();
+
+        let side = KeyCode::BTN_SIDE;
+        let back = ButtonTarget::Button(button(KeyCode::BTN_BACK));
+        let layer = cfg.layers.get("layer");
+        assert_eq!(
+            state.process(button_event(side, ButtonState::Pressed, 0), &cfg, layer),
+            back
+        );
+        // The layer is deactivated while the button is held.
+        assert_eq!(
+            state.process(button_event(side, ButtonState::Released, 0), &cfg, None),
+            back
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use input::event::pointer::ButtonState;
+
+use crate::config::{self, Button};
+
+use super::ButtonEvent;
+
+/// Holds back presses of chord buttons until a chord is completed or the chord window expires.
+#[derive(Debug, Default)]
+pub struct ChordState {
+    pending: Vec<ButtonEvent>,
+    deadline: Option<u64>,
+    active: Vec<ActiveChord>,
+}
+
+#[derive(Debug)]
+struct ActiveChord {
+    target: Button,
+    held: Vec<Button>,
+    released: bool,
+}
+
+impl ChordState {
+    pub fn deadline(&self) -> Option<u64> {
+        self.deadline
+    }
+
+    pub fn process(&mut self, ev: ButtonEvent, cfg: &config::Device) -> Vec<ButtonEvent> {
+        if cfg.chords.is_empty() {
+            return vec![ev];
+        }
+
+        match ev.state {
+            ButtonState::Pressed => self.press(ev, cfg),
+            ButtonState::Released => self.release(ev),
+        }
+    }
+
+    pub fn expire(&mut self, now: u64) -> Vec<ButtonEvent> {
+        match self.deadline {
+            Some(x) if x <= now => self.flush(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn press(&mut self, ev: ButtonEvent, cfg: &config::Device) -> Vec<ButtonEvent> {
+        if !cfg.chords.iter().any(|x| x.buttons.contains(&ev.button)) {
+            let mut res = self.flush();
+            res.push(ev);
+            return res;
+        }
+
+        if self.pending.is_empty() {
+            self.deadline = Some(ev.time + cfg.chord_window_usec());
+        }
+        self.pending.push(ev);
+
+        let buttons = self.pending.iter().map(|x| x.button).collect::<Vec<_>>();
+        if let Some(chord) = cfg.chords.iter().find(|x| x.matches(&buttons)) {
+            self.pending.clear();
+            self.deadline = None;
+            self.active.push(ActiveChord {
+                target: chord.target,
+                held: buttons,
+                released: false,
+            });
+            return vec![ButtonEvent {
+                button: chord.target,
+                ..ev
+            }];
+        }
+
+        if cfg.chords.iter().any(|x| x.contains_all(&buttons)) {
+            Vec::new()
+        } else {
+            self.flush()
+        }
+    }
+
+    fn release(&mut self, ev: ButtonEvent) -> Vec<ButtonEvent> {
+        if let Some(i) = self.active.iter().position(|x| x.held.contains(&ev.button)) {
+            let chord = &mut self.active[i];
+            chord.held.retain(|x| *x != ev.button);
+
+            // The target is released with the first released source button, the rest are swallowed.
+            let mut res = Vec::new();
+            if !chord.released {
+                chord.released = true;
+                res.push(ButtonEvent {
+                    button: chord.target,
+                    ..ev
+                });
+            }
+            if chord.held.is_empty() {
+                self.active.remove(i);
+            }
+            return res;
+        }
+
+        let mut res = if self.pending.iter().any(|x| x.button == ev.button) {
+            self.flush()
+        } else {
+            Vec::new()
+        };
+        res.push(ev);
+        res
+    }
+
+    fn flush(&mut self) -> Vec<ButtonEvent> {
+        self.deadline = None;
+        std::mem::take(&mut self.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+    use crate::sink_event::test_util::{button_event, device_config};
+
+    fn press(code: KeyCode, time: u64) -> ButtonEvent {
+        button_event(code, ButtonState::Pressed, time)
+    }
+
+    fn release(code: KeyCode, time: u64) -> ButtonEvent {
+        button_event(code, ButtonState::Released, time)
+    }
+
+    const CONFIG: &str = r#"
+        chords:
+          - buttons: [BTN_LEFT, BTN_RIGHT]
+            target: BTN_MIDDLE
+        "#;
+
+    #[test]
+    fn test_chord() {
+        let cfg = device_config(CONFIG);
+        let mut state = ChordState::default();
+
+        assert_eq!(state.process(press(KeyCode::BTN_LEFT, 0), &cfg), vec![]);
+        assert_eq!(state.deadline(), Some(50_000));
+        assert_eq!(
+            state.process(press(KeyCode::BTN_RIGHT, 10_000), &cfg),
+            vec![press(KeyCode::BTN_MIDDLE, 10_000)]
+        );
+        assert_eq!(state.deadline(), None);
+        assert_eq!(
+            state.process(release(KeyCode::BTN_RIGHT, 20_000), &cfg),
+            vec![release(KeyCode::BTN_MIDDLE, 20_000)]
+        );
+        assert_eq!(
+            state.process(release(KeyCode::BTN_LEFT, 30_000), &cfg),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_window_expired() {
+        let cfg = device_config(CONFIG);
+        let mut state = ChordState::default();
+
+        assert_eq!(state.process(press(KeyCode::BTN_LEFT, 0), &cfg), vec![]);
+        assert_eq!(state.expire(49_999), vec![]);
+        assert_eq!(state.expire(50_000), vec![press(KeyCode::BTN_LEFT, 0)]);
+        assert_eq!(state.deadline(), None);
+        assert_eq!(
+            state.process(release(KeyCode::BTN_LEFT, 60_000), &cfg),
+            vec![release(KeyCode::BTN_LEFT, 60_000)]
+        );
+    }
+
+    #[test]
+    fn test_released_within_window() {
+        let cfg = device_config(CONFIG);
+        let mut state = ChordState::default();
+
+        assert_eq!(state.process(press(KeyCode::BTN_LEFT, 0), &cfg), vec![]);
+        assert_eq!(
+            state.process(release(KeyCode::BTN_LEFT, 10_000), &cfg),
+            vec![
+                press(KeyCode::BTN_LEFT, 0),
+                release(KeyCode::BTN_LEFT, 10_000)
+            ]
+        );
+        assert_eq!(state.deadline(), None);
+    }
+
+    #[test]
+    fn test_other_button_flushes_pending() {
+        let cfg = device_config(CONFIG);
+        let mut state = ChordState::default();
+
+        assert_eq!(state.process(press(KeyCode::BTN_LEFT, 0), &cfg), vec![]);
+        assert_eq!(
+            state.process(press(KeyCode::BTN_SIDE, 10_000), &cfg),
+            vec![
+                press(KeyCode::BTN_LEFT, 0),
+                press(KeyCode::BTN_SIDE, 10_000)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_chords() {
+        let cfg = config::Device::default();
+        let mut state = ChordState::default();
+
+        assert_eq!(
+            state.process(press(KeyCode::BTN_LEFT, 0), &cfg),
+            vec![press(KeyCode::BTN_LEFT, 0)]
+        );
+        assert_eq!(state.deadline(), None);
+    }
+}
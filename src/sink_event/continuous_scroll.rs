@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use input::event::pointer::ButtonState;
+
+use crate::config::{Button, ScrollDirection};
+
+use super::{ButtonEvent, Scroll, TICK_USEC};
+
+/// Scrolls continuously while a source button is held.
+#[derive(Debug, Default)]
+pub struct ContinuousScrollState {
+    active: HashMap<Button, ActiveScroll>,
+}
+
+#[derive(Debug)]
+struct ActiveScroll {
+    direction: ScrollDirection,
+    /// Scroll rate in notches per second.
+    rate: f64,
+    last: u64,
+}
+
+impl ContinuousScrollState {
+    pub fn deadline(&self) -> Option<u64> {
+        self.active.values().map(|x| x.last + TICK_USEC).min()
+    }
+
+    pub fn process(&mut self, ev: ButtonEvent, direction: ScrollDirection, rate: f64) {
+        match ev.state {
+            ButtonState::Pressed => {
+                self.active.insert(
+                    ev.button,
+                    ActiveScroll {
+                        direction,
+                        rate,
+                        last: ev.time,
+                    },
+                );
+            }
+            ButtonState::Released => {
+                self.active.remove(&ev.button);
+            }
+        }
+    }
+
+    /// Returns scrolls which are due until `now`.
+    pub fn expire(&mut self, now: u64) -> Vec<Scroll> {
+        let mut res = Vec::new();
+        for x in self.active.values_mut() {
+            if x.last + TICK_USEC > now {
+                continue;
+            }
+            let delta = x.rate * (now - x.last) as f64 / 1_000_000.0;
+            x.last = now;
+            res.push(Scroll::directed(x.direction, delta * 120.0));
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+    use crate::sink_event::test_util::{button_event, vertical};
+
+    #[test]
+    fn test_scroll() {
+        let mut state = ContinuousScrollState::default();
+
+        state.process(
+            button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0),
+            ScrollDirection::Down,
+            25.0,
+        );
+        assert_eq!(state.deadline(), Some(10_000));
+        assert_eq!(state.expire(9_999), vec![]);
+        assert_eq!(state.expire(20_000), vec![vertical(60.0)]);
+        assert_eq!(state.deadline(), Some(30_000));
+        assert_eq!(state.expire(40_000), vec![vertical(60.0)]);
+
+        state.process(
+            button_event(KeyCode::BTN_SIDE, ButtonState::Released, 55_000),
+            ScrollDirection::Down,
+            25.0,
+        );
+        assert_eq!(state.deadline(), None);
+        assert_eq!(state.expire(60_000), vec![]);
+    }
+
+    #[test]
+    fn test_direction() {
+        let mut state = ContinuousScrollState::default();
+
+        state.process(
+            button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0),
+            ScrollDirection::Up,
+            100.0,
+        );
+        assert_eq!(state.expire(10_000), vec![vertical(-120.0)]);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::config::AccelCurve;
+
+/// Motions which are farther apart than this are measured as this far apart.
+const MAX_INTERVAL_USEC: u64 = 100_000;
+
+/// Lower bound of the interval, so that motions at the same time do not have infinite speed.
+const MIN_INTERVAL_USEC: u64 = 100;
+
+/// Accelerates the unaccelerated motion by a custom curve of its speed, which is measured from the event times.
+#[derive(Debug, Default)]
+pub struct CustomAccelState {
+    /// Time of the last motion.
+    last: Option<u64>,
+}
+
+impl CustomAccelState {
+    /// Returns the accelerated deltas.
+    pub fn process(&mut self, dx: f64, dy: f64, time: u64, curve: &AccelCurve) -> (f64, f64) {
+        let interval = self
+            .last
+            .map_or(MAX_INTERVAL_USEC, |x| time.saturating_sub(x))
+            .clamp(MIN_INTERVAL_USEC, MAX_INTERVAL_USEC);
+        self.last = Some(time);
+
+        let speed = dx.hypot(dy) / (interval as f64 / 1000.0);
+        if speed == 0.0 {
+            return (0.0, 0.0);
+        }
+        let factor = curve.speed(speed) / speed;
+        (dx * factor, dy * factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve() -> AccelCurve {
+        serde_yaml::from_str("{points: [0, 1, 4], step: 1}").unwrap()
+    }
+
+    #[test]
+    fn test_accel() {
+        let curve = curve();
+        let mut state = CustomAccelState::default();
+
+        // The first motion is measured over the maximum interval: 10 units per 100ms
+        assert_eq!(state.process(6.0, 8.0, 0, &curve), (6.0, 8.0));
+        // 2 units per ms to 4
+        assert_eq!(state.process(6.0, 8.0, 5_000, &curve), (12.0, 16.0));
+        // 3 units per ms, extrapolated to 7
+        let (dx, dy) = state.process(3.0, 0.0, 6_000, &curve);
+        assert!((dx - 7.0).abs() < 1e-9 && dy == 0.0);
+        // 0.5 units per ms
+        assert_eq!(state.process(0.0, -5.0, 16_000, &curve), (0.0, -5.0));
+        assert_eq!(state.process(0.0, 0.0, 20_000, &curve), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::config;
+
+use super::Scroll;
+
+/// Drops motion scrolls until the travel of the gesture exceeds the dead zone.
+#[derive(Debug, Default)]
+pub struct DeadZoneState {
+    /// Travel of the current gesture.
+    travel: f64,
+}
+
+impl DeadZoneState {
+    pub fn process(&mut self, scroll: Scroll, stop: bool, cfg: &config::Device) -> Scroll {
+        let dead_zone = match &cfg.motion_scroll_dead_zone {
+            Some(x) => x.value(),
+            None => return scroll,
+        };
+        if stop {
+            self.travel = 0.0;
+            return scroll;
+        }
+
+        let vertical = scroll.vertical.unwrap_or(0.0);
+        let horizontal = scroll.horizontal.unwrap_or(0.0);
+        self.travel += vertical.hypot(horizontal);
+        if self.travel > dead_zone {
+            scroll
+        } else {
+            Scroll::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink_event::test_util::{device_config, scroll};
+
+    const CONFIG: &str = "motion_scroll_dead_zone: 20";
+
+    #[test]
+    fn test_jitter() {
+        let cfg = device_config(CONFIG);
+        let mut state = DeadZoneState::default();
+
+        for x in [scroll(3.0, 4.0), scroll(-3.0, -4.0), scroll(0.0, 5.0)] {
+            assert_eq!(state.process(x, false, &cfg), Scroll::default());
+        }
+        assert_eq!(
+            state.process(scroll(0.0, 0.0), true, &cfg),
+            scroll(0.0, 0.0)
+        );
+        // The travel is reset by the end of the gesture.
+        assert_eq!(
+            state.process(scroll(8.0, 6.0), false, &cfg),
+            Scroll::default()
+        );
+    }
+
+    #[test]
+    fn test_activation() {
+        let cfg = device_config(CONFIG);
+        let mut state = DeadZoneState::default();
+
+        assert_eq!(
+            state.process(scroll(12.0, 0.0), false, &cfg),
+            Scroll::default()
+        );
+        assert_eq!(
+            state.process(scroll(8.0, 0.0), false, &cfg),
+            Scroll::default()
+        );
+        assert_eq!(
+            state.process(scroll(1.0, 0.0), false, &cfg),
+            scroll(1.0, 0.0)
+        );
+        assert_eq!(
+            state.process(scroll(-1.0, 0.0), false, &cfg),
+            scroll(-1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_disabled() {
+        let cfg = device_config("{}");
+        let mut state = DeadZoneState::default();
+
+        assert_eq!(
+            state.process(scroll(1.0, 0.0), false, &cfg),
+            scroll(1.0, 0.0)
+        );
+    }
+}
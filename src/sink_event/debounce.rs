@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use input::event::pointer::ButtonState;
+use log::debug;
+
+use crate::config::{self, Button};
+
+use super::ButtonEvent;
+
+/// Swallows presses which arrive within the debounce time after a release of the same button.
+#[derive(Debug, Default)]
+pub struct DebounceState {
+    buttons: HashMap<Button, ButtonDebounce>,
+    suppressed: u64,
+}
+
+#[derive(Debug, Default)]
+struct ButtonDebounce {
+    last_release: Option<u64>,
+    suppressing: bool,
+}
+
+impl DebounceState {
+    /// Returns the number of suppressed bounces.
+    pub fn suppressed(&self) -> u64 {
+        self.suppressed
+    }
+
+    /// Returns true if the event should be forwarded.
+    pub fn process(&mut self, ev: ButtonEvent, cfg: &config::Device) -> bool {
+        let time = match cfg
+            .debounce_ms
+            .as_ref()
+            .and_then(|x| x.time_usec(ev.button))
+        {
+            Some(x) => x,
+            None => return true,
+        };
+
+        let button = self.buttons.entry(ev.button).or_default();
+        match ev.state {
+            ButtonState::Pressed => {
+                if button
+                    .last_release
+                    .is_some_and(|x| ev.time.saturating_sub(x) < time)
+                {
+                    button.suppressing = true;
+                    self.suppressed += 1;
+                    debug!(
+                        "suppressed bounce of {:?} ({} in total)",
+                        ev.button, self.suppressed
+                    );
+                    false
+                } else {
+                    true
+                }
+            }
+            ButtonState::Released => {
+                // Bounces may continue, so the window starts again from the last release.
+                button.last_release = Some(ev.time);
+                !std::mem::take(&mut button.suppressing)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+    use crate::sink_event::test_util::{button_event, device_config};
+
+    const CONFIG: &str = "debounce_ms: 30";
+
+    #[test]
+    fn test_bounce() {
+        let cfg = device_config(CONFIG);
+        let mut state = DebounceState::default();
+
+        let left = KeyCode::BTN_LEFT;
+        assert!(state.process(button_event(left, ButtonState::Pressed, 0), &cfg));
+        assert!(state.process(button_event(left, ButtonState::Released, 10_000), &cfg));
+        assert!(!state.process(button_event(left, ButtonState::Pressed, 20_000), &cfg));
+        assert!(!state.process(button_event(left, ButtonState::Released, 25_000), &cfg));
+        assert!(!state.process(button_event(left, ButtonState::Pressed, 50_000), &cfg));
+        assert!(!state.process(button_event(left, ButtonState::Released, 60_000), &cfg));
+        assert!(state.process(button_event(left, ButtonState::Pressed, 90_000), &cfg));
+        assert!(state.process(button_event(left, ButtonState::Released, 100_000), &cfg));
+        assert_eq!(state.suppressed(), 2);
+    }
+
+    #[test]
+    fn test_other_button() {
+        let cfg = device_config(CONFIG);
+        let mut state = DebounceState::default();
+
+        let left = KeyCode::BTN_LEFT;
+        let right = KeyCode::BTN_RIGHT;
+        assert!(state.process(button_event(left, ButtonState::Pressed, 0), &cfg));
+        assert!(state.process(button_event(left, ButtonState::Released, 10_000), &cfg));
+        assert!(state.process(button_event(right, ButtonState::Pressed, 20_000), &cfg));
+        assert!(state.process(button_event(right, ButtonState::Released, 30_000), &cfg));
+        assert_eq!(state.suppressed(), 0);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let cfg = config::Device::default();
+        let mut state = DebounceState::default();
+
+        let left = KeyCode::BTN_LEFT;
+        assert!(state.process(button_event(left, ButtonState::Pressed, 0), &cfg));
+        assert!(state.process(button_event(left, ButtonState::Released, 10_000), &cfg));
+        assert!(state.process(button_event(left, ButtonState::Pressed, 20_000), &cfg));
+        assert!(state.process(button_event(left, ButtonState::Released, 30_000), &cfg));
+    }
+}
@@ -0,0 +1,120 @@
+use evdev::KeyCode;
+use input::event::pointer::ButtonState;
+
+use crate::config;
+
+use super::ButtonEvent;
+
+/// Latches `BTN_LEFT` down when it is held longer than the drag lock time, until the next click.
+#[derive(Debug, Default)]
+pub struct DragLockState {
+    phase: Phase,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+enum Phase {
+    #[default]
+    Idle,
+    Held {
+        since: u64,
+    },
+    Latched,
+    Unlatching,
+}
+
+impl DragLockState {
+    /// Returns true if the event should be forwarded.
+    pub fn process(&mut self, ev: ButtonEvent, cfg: &config::Device) -> bool {
+        let hold_time = match cfg.drag_lock_usec() {
+            Some(x) => x,
+            None => return true,
+        };
+        if ev.button.code() != KeyCode::BTN_LEFT.code() {
+            return true;
+        }
+
+        let (phase, forward) = match (&self.phase, ev.state) {
+            (Phase::Idle, ButtonState::Pressed) => (Phase::Held { since: ev.time }, true),
+            (Phase::Held { since }, ButtonState::Released) => {
+                if ev.time.saturating_sub(*since) >= hold_time {
+                    (Phase::Latched, false)
+                } else {
+                    (Phase::Idle, true)
+                }
+            }
+            (Phase::Latched, ButtonState::Pressed) => (Phase::Unlatching, false),
+            (Phase::Unlatching, ButtonState::Released) => (Phase::Idle, true),
+            // Unbalanced events, e.g. the button is held on another path. Just forward it.
+            (_, ButtonState::Pressed) => (Phase::Held { since: ev.time }, true),
+            (_, ButtonState::Released) => (Phase::Idle, true),
+        };
+        self.phase = phase;
+        forward
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink_event::test_util::{button_event, device_config};
+
+    const CONFIG: &str = "drag_lock_ms: 500";
+
+    #[test]
+    fn test_short_click() {
+        let cfg = device_config(CONFIG);
+        let mut state = DragLockState::default();
+
+        let left = KeyCode::BTN_LEFT;
+        assert!(state.process(button_event(left, ButtonState::Pressed, 0), &cfg));
+        assert!(state.process(button_event(left, ButtonState::Released, 499_999), &cfg));
+        assert_eq!(state.phase, Phase::Idle);
+    }
+
+    #[test]
+    fn test_latch() {
+        let cfg = device_config(CONFIG);
+        let mut state = DragLockState::default();
+
+        let left = KeyCode::BTN_LEFT;
+        assert!(state.process(button_event(left, ButtonState::Pressed, 0), &cfg));
+        assert!(!state.process(button_event(left, ButtonState::Released, 500_000), &cfg));
+        assert_eq!(state.phase, Phase::Latched);
+        assert!(!state.process(button_event(left, ButtonState::Pressed, 600_000), &cfg));
+        assert!(state.process(button_event(left, ButtonState::Released, 700_000), &cfg));
+        assert_eq!(state.phase, Phase::Idle);
+    }
+
+    #[test]
+    fn test_other_button() {
+        let cfg = device_config(CONFIG);
+        let mut state = DragLockState::default();
+
+        let right = KeyCode::BTN_RIGHT;
+        assert!(state.process(button_event(right, ButtonState::Pressed, 0), &cfg));
+        assert!(state.process(button_event(right, ButtonState::Released, 1_000_000), &cfg));
+        assert_eq!(state.phase, Phase::Idle);
+    }
+
+    #[test]
+    fn test_de() {
+        let cfg = device_config("drag_lock_ms: 500");
+        assert_eq!(cfg.drag_lock_usec(), Some(500_000));
+        assert!(
+            serde_yaml::from_str::<config::Device>("{match_rule: {name: moo}, drag_lock_ms: 0}")
+                .is_err()
+        );
+        let cfg = device_config("drag_lock_ms: 18446744073709551615");
+        assert_eq!(cfg.drag_lock_usec(), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let cfg = config::Device::default();
+        let mut state = DragLockState::default();
+
+        let left = KeyCode::BTN_LEFT;
+        assert!(state.process(button_event(left, ButtonState::Pressed, 0), &cfg));
+        assert!(state.process(button_event(left, ButtonState::Released, 1_000_000), &cfg));
+    }
+}
@@ -0,0 +1,82 @@
+use input::event::pointer::ButtonState;
+
+use super::ButtonEvent;
+
+/// Holds back the press of a modifier button until its release, and drops both if the modifier was used meanwhile.
+#[derive(Debug, Default)]
+pub struct HeldModifier {
+    pressed: Option<ButtonEvent>,
+    used: bool,
+}
+
+impl HeldModifier {
+    pub fn is_held(&self) -> bool {
+        self.pressed.is_some()
+    }
+
+    /// Marks the modifier as used if it is held, and returns true if it is held.
+    pub fn use_if_held(&mut self) -> bool {
+        self.used |= self.is_held();
+        self.is_held()
+    }
+
+    /// Returns the button events to pass on for an event of the modifier button.
+    pub fn process(&mut self, ev: ButtonEvent) -> Vec<ButtonEvent> {
+        match ev.state {
+            ButtonState::Pressed => {
+                self.pressed = Some(ev);
+                self.used = false;
+                Vec::new()
+            }
+            ButtonState::Released => {
+                let pressed = self.pressed.take();
+                if std::mem::take(&mut self.used) {
+                    return Vec::new();
+                }
+                pressed.into_iter().chain([ev]).collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+    use crate::sink_event::test_util::button_event;
+
+    #[test]
+    fn test_used() {
+        let mut modifier = HeldModifier::default();
+
+        assert!(!modifier.use_if_held());
+        let press = button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0);
+        assert_eq!(modifier.process(press), vec![]);
+        assert!(modifier.use_if_held());
+        assert!(modifier.use_if_held());
+        let release = button_event(KeyCode::BTN_SIDE, ButtonState::Released, 100);
+        assert_eq!(modifier.process(release), vec![]);
+        assert!(!modifier.is_held());
+        assert!(!modifier.use_if_held());
+    }
+
+    #[test]
+    fn test_click() {
+        let mut modifier = HeldModifier::default();
+
+        let press = button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0);
+        assert_eq!(modifier.process(press), vec![]);
+        assert!(modifier.is_held());
+        let release = button_event(KeyCode::BTN_SIDE, ButtonState::Released, 100);
+        assert_eq!(modifier.process(release), vec![press, release]);
+    }
+
+    #[test]
+    fn test_unbalanced_release() {
+        let mut modifier = HeldModifier::default();
+
+        let release = button_event(KeyCode::BTN_SIDE, ButtonState::Released, 100);
+        assert_eq!(modifier.process(release), vec![release]);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::config;
+
+use super::ButtonEvent;
+use super::held_modifier::HeldModifier;
+
+/// Turns the vertical wheel into horizontal scroll while the modifier button is held.
+#[derive(Debug, Default)]
+pub struct HorizontalScrollState {
+    modifier: HeldModifier,
+}
+
+impl HorizontalScrollState {
+    /// Returns true if the wheel should scroll horizontally, marking the modifier as used.
+    pub fn use_wheel(&mut self) -> bool {
+        self.modifier.use_if_held()
+    }
+
+    /// Returns the button events to pass on.
+    pub fn process(&mut self, ev: ButtonEvent, cfg: &config::Device) -> Vec<ButtonEvent> {
+        if cfg.horizontal_scroll_modifier != Some(ev.button) {
+            return vec![ev];
+        }
+        self.modifier.process(ev)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use input::event::pointer::ButtonState;
+
+    use evdev::KeyCode;
+
+    use super::*;
+    use crate::sink_event::test_util::{button_event, device_config};
+
+    const CONFIG: &str = "horizontal_scroll_modifier: BTN_SIDE";
+
+    #[test]
+    fn test_wheel_used() {
+        let cfg = device_config(CONFIG);
+        let mut state = HorizontalScrollState::default();
+
+        assert!(!state.use_wheel());
+        let press = button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0);
+        assert_eq!(state.process(press, &cfg), vec![]);
+        assert!(state.use_wheel());
+        assert!(state.use_wheel());
+        let release = button_event(KeyCode::BTN_SIDE, ButtonState::Released, 100);
+        assert_eq!(state.process(release, &cfg), vec![]);
+        assert!(!state.use_wheel());
+    }
+
+    #[test]
+    fn test_other_button() {
+        let cfg = device_config(CONFIG);
+        let mut state = HorizontalScrollState::default();
+
+        let press = button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, 0);
+        assert_eq!(state.process(press, &cfg), vec![press]);
+        assert!(!state.use_wheel());
+    }
+}
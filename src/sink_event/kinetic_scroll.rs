@@ -0,0 +1,158 @@
+use std::collections::VecDeque;
+
+use crate::config;
+
+use super::{Scroll, TICK_USEC};
+
+/// Time window before motion scrolling stops in which its velocity is measured.
+const VELOCITY_WINDOW_USEC: u64 = 100_000;
+
+/// Keeps scrolling with decaying velocity after motion scrolling stops.
+#[derive(Debug, Default)]
+pub struct KineticScrollState {
+    /// Recent motion scrolls with their times.
+    samples: VecDeque<(u64, Scroll)>,
+    active: Option<ActiveTail>,
+}
+
+#[derive(Debug)]
+struct ActiveTail {
+    last: u64,
+    /// Velocities in hi-res units per second.
+    vertical: f64,
+    horizontal: f64,
+}
+
+impl KineticScrollState {
+    pub fn deadline(&self) -> Option<u64> {
+        self.active.as_ref().map(|x| x.last + TICK_USEC)
+    }
+
+    pub fn stop(&mut self) {
+        self.active = None;
+    }
+
+    /// Records a motion scroll, and starts scrolling on if it is the stop of motion scrolling.
+    pub fn process(&mut self, scroll: Scroll, stop: bool, time: u64, cfg: &config::Device) {
+        self.stop();
+        if !stop {
+            self.samples.push_back((time, scroll));
+            while self
+                .samples
+                .front()
+                .is_some_and(|(x, _)| x + VELOCITY_WINDOW_USEC < time)
+            {
+                self.samples.pop_front();
+            }
+            return;
+        }
+
+        let samples = std::mem::take(&mut self.samples);
+        let kinetic = match &cfg.kinetic_scroll {
+            Some(x) => x,
+            None => return,
+        };
+        let velocity = |value: fn(&Scroll) -> Option<f64>| {
+            let sum = samples
+                .iter()
+                .filter(|(x, _)| x + VELOCITY_WINDOW_USEC >= time)
+                .filter_map(|(_, x)| value(x))
+                .sum::<f64>();
+            sum * 1_000_000.0 / VELOCITY_WINDOW_USEC as f64
+        };
+        let tail = ActiveTail {
+            last: time,
+            vertical: velocity(|x| x.vertical),
+            horizontal: velocity(|x| x.horizontal),
+        };
+        if tail.vertical.hypot(tail.horizontal) >= kinetic.min_velocity {
+            self.active = Some(tail);
+        }
+    }
+
+    /// Returns the scroll which is due until `now`.
+    pub fn expire(&mut self, now: u64, cfg: &config::Device) -> Option<Scroll> {
+        let kinetic = cfg.kinetic_scroll.as_ref()?;
+        let tail = self.active.as_mut()?;
+        if tail.last + TICK_USEC > now {
+            return None;
+        }
+
+        let elapsed = (now - tail.last) as f64 / 1_000_000.0;
+        tail.last = now;
+        let scroll = Scroll {
+            vertical: (tail.vertical != 0.0).then_some(tail.vertical * elapsed),
+            horizontal: (tail.horizontal != 0.0).then_some(tail.horizontal * elapsed),
+        };
+
+        let decay = (-kinetic.friction * elapsed).exp();
+        tail.vertical *= decay;
+        tail.horizontal *= decay;
+        if tail.vertical.hypot(tail.horizontal) < kinetic.min_velocity {
+            self.stop();
+        }
+        Some(scroll)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink_event::test_util::{device_config, vertical};
+
+    const CONFIG: &str = r#"
+        kinetic_scroll:
+          friction: 10
+          min_velocity: 500
+        "#;
+
+    #[test]
+    fn test_tail() {
+        let cfg = device_config(CONFIG);
+        let mut state = KineticScrollState::default();
+
+        // Scrolls older than the velocity window are not counted.
+        state.process(vertical(1000.0), false, 0, &cfg);
+        state.process(vertical(60.0), false, 150_000, &cfg);
+        state.process(vertical(60.0), false, 200_000, &cfg);
+        assert_eq!(state.deadline(), None);
+
+        state.process(vertical(0.0), true, 200_000, &cfg);
+        assert_eq!(state.deadline(), Some(210_000));
+        assert_eq!(state.expire(209_999, &cfg), None);
+        let scroll = state.expire(210_000, &cfg).unwrap();
+        assert_eq!(scroll.horizontal, None);
+        assert!((scroll.vertical.unwrap() - 12.0).abs() < 1e-9);
+
+        // The velocity decays by e^-0.1 each interval until it falls below 500.
+        let mut count = 1;
+        while state.expire(210_000 + count * 10_000, &cfg).is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 9);
+        assert_eq!(state.deadline(), None);
+    }
+
+    #[test]
+    fn test_slow_stop() {
+        let cfg = device_config(CONFIG);
+        let mut state = KineticScrollState::default();
+
+        state.process(vertical(40.0), false, 0, &cfg);
+        state.process(vertical(0.0), true, 10_000, &cfg);
+        assert_eq!(state.deadline(), None);
+    }
+
+    #[test]
+    fn test_stop() {
+        let cfg = device_config(CONFIG);
+        let mut state = KineticScrollState::default();
+
+        state.process(vertical(-200.0), false, 0, &cfg);
+        state.process(vertical(0.0), true, 10_000, &cfg);
+        assert_eq!(state.deadline(), Some(20_000));
+        state.stop();
+        assert_eq!(state.deadline(), None);
+        assert_eq!(state.expire(20_000, &cfg), None);
+    }
+}
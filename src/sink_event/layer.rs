@@ -0,0 +1,175 @@
+use input::event::pointer::ButtonState;
+
+use crate::config::{self, Layer, LayerMode};
+
+use super::ButtonEvent;
+
+/// Tracks active layers by their buttons.
+#[derive(Debug, Default)]
+pub struct LayerState {
+    /// Names of the active layers in activation order.
+    active: Vec<String>,
+}
+
+impl LayerState {
+    /// Returns the most recently activated layer.
+    pub fn active<'a>(&self, cfg: &'a config::Device) -> Option<&'a Layer> {
+        self.active.last().and_then(|x| cfg.layers.get(x))
+    }
+
+    /// Returns true if the event is consumed as a layer button.
+    pub fn process(&mut self, ev: ButtonEvent, cfg: &config::Device) -> bool {
+        let (name, layer) = match cfg.layers.iter().find(|(_, x)| x.button == ev.button) {
+            Some(x) => x,
+            None => return false,
+        };
+
+        match (layer.mode, ev.state) {
+            (LayerMode::Hold | LayerMode::OneShot, ButtonState::Pressed) => self.activate(name),
+            (LayerMode::Hold, ButtonState::Released) => self.deactivate(name),
+            (LayerMode::Toggle, ButtonState::Pressed) => {
+                if self.active.contains(name) {
+                    self.deactivate(name);
+                } else {
+                    self.activate(name);
+                }
+            }
+            (LayerMode::Toggle | LayerMode::OneShot, ButtonState::Released) => {}
+        }
+        true
+    }
+
+    /// Deactivates one-shot layers after a press of another button is mapped.
+    pub fn finish_one_shot(&mut self, cfg: &config::Device) {
+        self.active.retain(|x| {
+            cfg.layers
+                .get(x)
+                .is_some_and(|x| x.mode != LayerMode::OneShot)
+        });
+    }
+
+    fn activate(&mut self, name: &str) {
+        self.deactivate(name);
+        self.active.push(name.to_string());
+    }
+
+    fn deactivate(&mut self, name: &str) {
+        self.active.retain(|x| x != name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+    use crate::sink_event::test_util::{button_event, device_config};
+
+    const CONFIG: &str = r#"
+        layers:
+          hold:
+            button: BTN_EXTRA
+          toggle:
+            button: BTN_SIDE
+            mode: toggle
+          one_shot:
+            button: BTN_TASK
+            mode: one_shot
+        "#;
+
+    fn active_name(state: &LayerState) -> Option<&str> {
+        state.active.last().map(String::as_str)
+    }
+
+    #[test]
+    fn test_hold() {
+        let cfg = device_config(CONFIG);
+        let mut state = LayerState::default();
+
+        assert!(state.process(
+            button_event(KeyCode::BTN_EXTRA, ButtonState::Pressed, 0),
+            &cfg
+        ));
+        assert_eq!(active_name(&state), Some("hold"));
+        assert!(!state.process(
+            button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, 0),
+            &cfg
+        ));
+        state.finish_one_shot(&cfg);
+        assert_eq!(active_name(&state), Some("hold"));
+        assert!(state.process(
+            button_event(KeyCode::BTN_EXTRA, ButtonState::Released, 0),
+            &cfg
+        ));
+        assert_eq!(active_name(&state), None);
+    }
+
+    #[test]
+    fn test_toggle() {
+        let cfg = device_config(CONFIG);
+        let mut state = LayerState::default();
+
+        assert!(state.process(
+            button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0),
+            &cfg
+        ));
+        assert!(state.process(
+            button_event(KeyCode::BTN_SIDE, ButtonState::Released, 0),
+            &cfg
+        ));
+        assert_eq!(active_name(&state), Some("toggle"));
+        assert!(state.process(
+            button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0),
+            &cfg
+        ));
+        assert!(state.process(
+            button_event(KeyCode::BTN_SIDE, ButtonState::Released, 0),
+            &cfg
+        ));
+        assert_eq!(active_name(&state), None);
+    }
+
+    #[test]
+    fn test_one_shot() {
+        let cfg = device_config(CONFIG);
+        let mut state = LayerState::default();
+
+        assert!(state.process(
+            button_event(KeyCode::BTN_TASK, ButtonState::Pressed, 0),
+            &cfg
+        ));
+        assert!(state.process(
+            button_event(KeyCode::BTN_TASK, ButtonState::Released, 0),
+            &cfg
+        ));
+        assert_eq!(active_name(&state), Some("one_shot"));
+        assert!(!state.process(
+            button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, 0),
+            &cfg
+        ));
+        assert_eq!(active_name(&state), Some("one_shot"));
+        state.finish_one_shot(&cfg);
+        assert_eq!(active_name(&state), None);
+    }
+
+    #[test]
+    fn test_most_recent_layer() {
+        let cfg = device_config(CONFIG);
+        let mut state = LayerState::default();
+
+        state.process(
+            button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0),
+            &cfg,
+        );
+        state.process(
+            button_event(KeyCode::BTN_EXTRA, ButtonState::Pressed, 0),
+            &cfg,
+        );
+        assert_eq!(active_name(&state), Some("hold"));
+        state.process(
+            button_event(KeyCode::BTN_EXTRA, ButtonState::Released, 0),
+            &cfg,
+        );
+        assert_eq!(active_name(&state), Some("toggle"));
+    }
+}
@@ -0,0 +1,52 @@
+/// Carries fractions of the pointer motion over to the next event, so that slow motion is not lost.
+#[derive(Debug, Default)]
+pub struct MotionRemainderState {
+    /// Fractions of a unit which are not emitted yet.
+    x: f64,
+    y: f64,
+}
+
+impl MotionRemainderState {
+    /// Returns the whole units of `REL_X` and `REL_Y` to emit.
+    pub fn process(&mut self, dx: f64, dy: f64) -> (i32, i32) {
+        (take_whole(&mut self.x, dx), take_whole(&mut self.y, dy))
+    }
+}
+
+fn take_whole(remainder: &mut f64, delta: f64) -> i32 {
+    *remainder += delta;
+    let emitted = remainder.trunc();
+    *remainder -= emitted;
+    emitted as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slow_motion() {
+        let mut state = MotionRemainderState::default();
+
+        assert_eq!(state.process(0.4, -0.4), (0, 0));
+        assert_eq!(state.process(0.4, -0.4), (0, 0));
+        assert_eq!(state.process(0.4, -0.4), (1, -1));
+        assert_eq!(state.process(1.9, 0.3), (2, 0));
+    }
+
+    #[test]
+    fn test_total() {
+        let mut state = MotionRemainderState::default();
+        let deltas = [0.3, -0.7, 1.25, 0.45, -0.2, 2.6, -0.15, 0.9, 0.55];
+
+        let (mut x, mut y) = (0, 0);
+        for dx in deltas {
+            let (ex, ey) = state.process(dx, -dx);
+            x += ex;
+            y += ey;
+        }
+        // The sum of the deltas is 5.0.
+        assert_eq!((x, y), (5, -5));
+        assert!(state.x.abs() < 1e-9 && state.y.abs() < 1e-9);
+    }
+}
@@ -0,0 +1,87 @@
+use input::event::pointer::ButtonState;
+
+use crate::config;
+
+use super::ButtonEvent;
+
+/// Scales pointer motion down while the precision button is held or toggled on.
+#[derive(Debug, Default)]
+pub struct PrecisionState {
+    active: bool,
+}
+
+impl PrecisionState {
+    /// Returns the factor of pointer motion.
+    pub fn factor(&self, cfg: &config::Device) -> f64 {
+        if self.active {
+            cfg.precision_factor()
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns true if the button event is consumed by the precision button.
+    pub fn process_button(&mut self, ev: ButtonEvent, cfg: &config::Device) -> bool {
+        if cfg.precision_button != Some(ev.button) {
+            return false;
+        }
+        match ev.state {
+            ButtonState::Pressed if cfg.precision_button_lock == Some(true) => {
+                self.active = !self.active
+            }
+            ButtonState::Pressed => self.active = true,
+            ButtonState::Released if cfg.precision_button_lock == Some(true) => {}
+            ButtonState::Released => self.active = false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+    use crate::sink_event::test_util::{self, button_event};
+
+    fn device_config(lock: bool) -> config::Device {
+        test_util::device_config(&format!(
+            r#"
+            precision_button: BTN_SIDE
+            precision_button_lock: {lock}
+            precision_factor: 0.2
+            "#
+        ))
+    }
+
+    #[test]
+    fn test_hold() {
+        let cfg = device_config(false);
+        let mut state = PrecisionState::default();
+
+        assert_eq!(state.factor(&cfg), 1.0);
+        let press = button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0);
+        assert!(state.process_button(press, &cfg));
+        assert_eq!(state.factor(&cfg), 0.2);
+        let other = button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, 0);
+        assert!(!state.process_button(other, &cfg));
+        let release = button_event(KeyCode::BTN_SIDE, ButtonState::Released, 0);
+        assert!(state.process_button(release, &cfg));
+        assert_eq!(state.factor(&cfg), 1.0);
+    }
+
+    #[test]
+    fn test_lock() {
+        let cfg = device_config(true);
+        let mut state = PrecisionState::default();
+
+        let press = button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0);
+        let release = button_event(KeyCode::BTN_SIDE, ButtonState::Released, 0);
+        assert!(state.process_button(press, &cfg));
+        assert!(state.process_button(release, &cfg));
+        assert_eq!(state.factor(&cfg), 0.2);
+        assert!(state.process_button(press, &cfg));
+        assert!(state.process_button(release, &cfg));
+        assert_eq!(state.factor(&cfg), 1.0);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::config;
+
+use super::Scroll;
+
+/// Quantizes motion scrolls into whole notches, accumulating the travel between them.
+#[derive(Debug, Default)]
+pub struct RatchetState {
+    vertical: f64,
+    horizontal: f64,
+}
+
+impl RatchetState {
+    /// Returns the scroll of whole notches, where an axis is `None` until it travels a notch.
+    pub fn process(&mut self, scroll: Scroll, stop: bool, cfg: &config::Device) -> Scroll {
+        let ratchet = match &cfg.motion_scroll_ratchet {
+            Some(x) => x,
+            None => return scroll,
+        };
+        // Start over when motion scrolling stops.
+        if stop {
+            *self = Self::default();
+            return scroll;
+        }
+        Scroll {
+            vertical: process_axis(scroll.vertical, &mut self.vertical, ratchet.distance),
+            horizontal: process_axis(scroll.horizontal, &mut self.horizontal, ratchet.distance),
+        }
+    }
+}
+
+fn process_axis(v120: Option<f64>, travel: &mut f64, distance: f64) -> Option<f64> {
+    let v120 = v120?;
+    // Travel in the previous direction does not count toward a notch in the new one.
+    if v120 * *travel < 0.0 {
+        *travel = 0.0;
+    }
+    *travel += v120;
+    let notches = (*travel / distance).trunc();
+    *travel -= notches * distance;
+    (notches != 0.0).then_some(notches * 120.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink_event::test_util::{device_config, scroll, vertical};
+
+    const CONFIG: &str = r#"
+        motion_scroll_ratchet:
+          distance: 50
+        "#;
+
+    #[test]
+    fn test_notches() {
+        let cfg = device_config(CONFIG);
+        let mut state = RatchetState::default();
+
+        assert_eq!(
+            state.process(vertical(30.0), false, &cfg),
+            Scroll::default()
+        );
+        // Zero values within the gesture keep the travel.
+        assert_eq!(state.process(vertical(0.0), false, &cfg), Scroll::default());
+        assert_eq!(state.process(vertical(30.0), false, &cfg), vertical(120.0));
+        assert_eq!(state.process(vertical(95.0), false, &cfg), vertical(240.0));
+        assert_eq!(
+            state.process(vertical(-10.0), false, &cfg),
+            Scroll::default()
+        );
+        assert_eq!(
+            state.process(vertical(-40.0), false, &cfg),
+            vertical(-120.0)
+        );
+    }
+
+    #[test]
+    fn test_stop() {
+        let cfg = device_config(CONFIG);
+        let mut state = RatchetState::default();
+
+        assert_eq!(
+            state.process(scroll(40.0, -40.0), false, &cfg),
+            Scroll::default()
+        );
+        assert_eq!(
+            state.process(scroll(0.0, 0.0), true, &cfg),
+            scroll(0.0, 0.0)
+        );
+        assert_eq!(
+            state.process(scroll(40.0, -40.0), false, &cfg),
+            Scroll::default()
+        );
+        assert_eq!(
+            state.process(scroll(10.0, -10.0), false, &cfg),
+            scroll(120.0, -120.0)
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use input::event::pointer::ButtonState;
+
+use crate::config::{self, Button};
+
+use super::ButtonEvent;
+
+/// Repeats clicks of the mapped button while a source button is held.
+#[derive(Debug, Default)]
+pub struct RepeatState {
+    active: HashMap<Button, ActiveRepeat>,
+}
+
+#[derive(Debug)]
+struct ActiveRepeat {
+    target: Button,
+    next: u64,
+}
+
+impl RepeatState {
+    pub fn deadline(&self) -> Option<u64> {
+        self.active.values().map(|x| x.next).min()
+    }
+
+    /// Starts or stops repeating by the source event and its mapped event.
+    pub fn process(
+        &mut self,
+        source: ButtonEvent,
+        mapped: Option<ButtonEvent>,
+        cfg: &config::Device,
+    ) {
+        match source.state {
+            ButtonState::Pressed => {
+                if let (Some(repeat), Some(mapped)) = (cfg.repeat.get(&source.button), mapped) {
+                    self.active.insert(
+                        source.button,
+                        ActiveRepeat {
+                            target: mapped.button,
+                            next: source.time + repeat.delay_usec(),
+                        },
+                    );
+                }
+            }
+            ButtonState::Released => {
+                self.active.remove(&source.button);
+            }
+        }
+    }
+
+    /// Returns pairs of release and press of the repeated buttons which are due until `now`.
+    pub fn expire(&mut self, now: u64, cfg: &config::Device) -> Vec<(ButtonEvent, ButtonEvent)> {
+        let mut res = Vec::new();
+        for (source, active) in self.active.iter_mut() {
+            if active.next > now {
+                continue;
+            }
+            let interval = match cfg.repeat.get(source) {
+                Some(x) => x.interval_usec(),
+                None => continue,
+            };
+
+            let event = |state| ButtonEvent {
+                button: active.target,
+                state,
+                time: now,
+            };
+            res.push((event(ButtonState::Released), event(ButtonState::Pressed)));
+            // Skip missed repeats instead of emitting a burst of clicks.
+            while active.next <= now {
+                active.next += interval;
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+    use crate::sink_event::test_util::{button_event, device_config};
+
+    const CONFIG: &str = r#"
+        repeat:
+          BTN_SIDE:
+            delay_ms: 300
+            interval_ms: 100
+        "#;
+
+    #[test]
+    fn test_repeat() {
+        let cfg = device_config(CONFIG);
+        let mut state = RepeatState::default();
+
+        let press = button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0);
+        let mapped = button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, 0);
+        state.process(press, Some(mapped), &cfg);
+        assert_eq!(state.deadline(), Some(300_000));
+        assert_eq!(state.expire(299_999, &cfg), vec![]);
+
+        let pair = |time| {
+            (
+                button_event(KeyCode::BTN_LEFT, ButtonState::Released, time),
+                button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, time),
+            )
+        };
+        assert_eq!(state.expire(300_000, &cfg), vec![pair(300_000)]);
+        assert_eq!(state.deadline(), Some(400_000));
+        assert_eq!(state.expire(650_000, &cfg), vec![pair(650_000)]);
+        assert_eq!(state.deadline(), Some(700_000));
+
+        let release = button_event(KeyCode::BTN_SIDE, ButtonState::Released, 660_000);
+        state.process(release, None, &cfg);
+        assert_eq!(state.deadline(), None);
+        assert_eq!(state.expire(700_000, &cfg), vec![]);
+    }
+
+    #[test]
+    fn test_not_repeated() {
+        let cfg = device_config(CONFIG);
+        let mut state = RepeatState::default();
+
+        let press = button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, 0);
+        state.process(press, Some(press), &cfg);
+        assert_eq!(state.deadline(), None);
+
+        // Disabled buttons are not repeated.
+        let press = button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0);
+        state.process(press, None, &cfg);
+        assert_eq!(state.deadline(), None);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::config::ScrollAcceleration;
+
+use super::Scroll;
+
+/// Scrolls which are farther apart than this are not accelerated.
+const MAX_INTERVAL_USEC: u64 = 500_000;
+
+/// Lower bound of the interval, so that scrolls at the same time do not have infinite speed.
+const MIN_INTERVAL_USEC: u64 = 1_000;
+
+/// Accelerates scrolls by their speed, which is measured from the event times.
+#[derive(Debug, Default)]
+pub struct ScrollAccelerationState {
+    vertical: AxisSpeed,
+    horizontal: AxisSpeed,
+}
+
+#[derive(Debug, Default)]
+struct AxisSpeed {
+    /// Time and sign of the last scroll.
+    last: Option<(u64, f64)>,
+}
+
+impl ScrollAccelerationState {
+    pub fn process(
+        &mut self,
+        scroll: Scroll,
+        time: u64,
+        acceleration: Option<&ScrollAcceleration>,
+    ) -> Scroll {
+        let acceleration = match acceleration {
+            Some(x) => x,
+            None => return scroll,
+        };
+        Scroll {
+            vertical: scroll
+                .vertical
+                .map(|x| x * acceleration.multiplier(self.vertical.process(x, time))),
+            horizontal: scroll
+                .horizontal
+                .map(|x| x * acceleration.multiplier(self.horizontal.process(x, time))),
+        }
+    }
+}
+
+impl AxisSpeed {
+    /// Returns the speed in notches per second.
+    fn process(&mut self, v120: f64, time: u64) -> f64 {
+        if v120 == 0.0 {
+            return 0.0;
+        }
+        let last = self.last.replace((time, v120.signum()));
+        match last {
+            // The speed is measured only from a scroll in the same direction which is recent enough.
+            Some((last, sign)) if sign == v120.signum() && time <= last + MAX_INTERVAL_USEC => {
+                let interval = time.saturating_sub(last).max(MIN_INTERVAL_USEC);
+                v120.abs() / 120.0 * 1_000_000.0 / interval as f64
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink_event::test_util::vertical;
+
+    fn acceleration() -> ScrollAcceleration {
+        serde_yaml::from_str("[[5, 1], [25, 3]]").unwrap()
+    }
+
+    #[test]
+    fn test_accelerate() {
+        let acceleration = acceleration();
+        let mut state = ScrollAccelerationState::default();
+
+        assert_eq!(
+            state.process(vertical(120.0), 0, Some(&acceleration)),
+            vertical(120.0)
+        );
+        // 10 notches per second
+        assert_eq!(
+            state.process(vertical(120.0), 100_000, Some(&acceleration)),
+            vertical(180.0)
+        );
+        // 40 notches per second
+        assert_eq!(
+            state.process(vertical(120.0), 125_000, Some(&acceleration)),
+            vertical(360.0)
+        );
+        // Too slow
+        assert_eq!(
+            state.process(vertical(120.0), 1_000_000, Some(&acceleration)),
+            vertical(120.0)
+        );
+    }
+
+    #[test]
+    fn test_direction_change() {
+        let acceleration = acceleration();
+        let mut state = ScrollAccelerationState::default();
+
+        state.process(vertical(120.0), 0, Some(&acceleration));
+        assert_eq!(
+            state.process(vertical(-120.0), 10_000, Some(&acceleration)),
+            vertical(-120.0)
+        );
+        assert_eq!(
+            state.process(vertical(-120.0), 20_000, Some(&acceleration)),
+            vertical(-360.0)
+        );
+    }
+
+    #[test]
+    fn test_no_acceleration() {
+        let mut state = ScrollAccelerationState::default();
+
+        state.process(vertical(120.0), 0, None);
+        assert_eq!(
+            state.process(vertical(120.0), 10_000, None),
+            vertical(120.0)
+        );
+    }
+}
@@ -0,0 +1,84 @@
+/// Carries fractions of the wheel output over to the next event, so that small scrolls are not lost.
+#[derive(Debug, Default)]
+pub struct ScrollRemainderState {
+    vertical: AxisRemainder,
+    horizontal: AxisRemainder,
+}
+
+#[derive(Debug, Default)]
+struct AxisRemainder {
+    /// Fraction of a hi-res unit which is not emitted yet.
+    v120: f64,
+    /// Hi-res units emitted since the last whole notch.
+    notch_v120: i32,
+}
+
+impl ScrollRemainderState {
+    /// Returns the notches and hi-res units to emit for the vertical output.
+    pub fn vertical(&mut self, v120: f64) -> (i32, i32) {
+        self.vertical.process(v120)
+    }
+
+    /// Returns the notches and hi-res units to emit for the horizontal output.
+    pub fn horizontal(&mut self, v120: f64) -> (i32, i32) {
+        self.horizontal.process(v120)
+    }
+}
+
+impl AxisRemainder {
+    fn process(&mut self, v120: f64) -> (i32, i32) {
+        // Fractions in the previous direction would hold back the new one.
+        if v120 * self.v120 < 0.0 || v120 * f64::from(self.notch_v120) < 0.0 {
+            *self = Self::default();
+        }
+
+        self.v120 += v120;
+        let emitted = self.v120.trunc();
+        self.v120 -= emitted;
+        let emitted = emitted as i32;
+
+        self.notch_v120 += emitted;
+        let notches = self.notch_v120 / 120;
+        self.notch_v120 -= notches * 120;
+        (notches, emitted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whole_notches() {
+        let mut state = ScrollRemainderState::default();
+
+        assert_eq!(state.vertical(120.0), (1, 120));
+        assert_eq!(state.vertical(-240.0), (-2, -240));
+        assert_eq!(state.horizontal(360.0), (3, 360));
+    }
+
+    #[test]
+    fn test_fractions() {
+        let mut state = ScrollRemainderState::default();
+
+        assert_eq!(state.vertical(0.4), (0, 0));
+        assert_eq!(state.vertical(0.4), (0, 0));
+        assert_eq!(state.vertical(0.4), (0, 1));
+        assert_eq!(state.vertical(60.0), (0, 60));
+        assert_eq!(state.vertical(60.0), (1, 60));
+        assert_eq!(state.vertical(60.0), (0, 60));
+    }
+
+    #[test]
+    fn test_direction_change() {
+        let mut state = ScrollRemainderState::default();
+
+        assert_eq!(state.vertical(90.0), (0, 90));
+        assert_eq!(state.vertical(-90.5), (0, -90));
+        assert_eq!(state.vertical(-30.0), (-1, -30));
+        // The remainder of the other axis is kept.
+        assert_eq!(state.horizontal(-60.0), (0, -60));
+        assert_eq!(state.vertical(1.0), (0, 1));
+        assert_eq!(state.horizontal(-60.0), (-1, -60));
+    }
+}
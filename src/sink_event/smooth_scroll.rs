@@ -0,0 +1,154 @@
+use crate::config;
+
+use super::{Scroll, TICK_USEC};
+
+/// Spreads wheel scrolls into series of hi-res scrolls.
+#[derive(Debug, Default)]
+pub struct SmoothScrollState {
+    active: Vec<Animation>,
+}
+
+#[derive(Debug)]
+struct Animation {
+    scroll: Scroll,
+    start: u64,
+    last: u64,
+    /// Eased fraction of the scroll already emitted.
+    emitted: f64,
+}
+
+impl SmoothScrollState {
+    pub fn deadline(&self) -> Option<u64> {
+        self.active.iter().map(|x| x.last + TICK_USEC).min()
+    }
+
+    pub fn process(&mut self, scroll: Scroll, time: u64) {
+        self.active.push(Animation {
+            scroll,
+            start: time,
+            last: time,
+            emitted: 0.0,
+        });
+    }
+
+    /// Returns the sum of the scrolls which are due until `now`.
+    pub fn expire(&mut self, now: u64, cfg: &config::Device) -> Option<Scroll> {
+        let duration = cfg.smooth_scroll_usec();
+        let mut res: Option<Scroll> = None;
+        for x in self.active.iter_mut() {
+            if x.last + TICK_USEC > now {
+                continue;
+            }
+            x.last = now;
+            let progress = ease_out((now - x.start) as f64 / duration as f64);
+            add_fraction(&mut res, x.scroll, progress - x.emitted);
+            x.emitted = progress;
+        }
+        self.active.retain(|x| x.emitted < 1.0);
+        res
+    }
+
+    /// Returns the sum of the scrolls which are not emitted yet, stopping all of them.
+    pub fn flush(&mut self) -> Option<Scroll> {
+        let mut res = None;
+        for x in self.active.drain(..) {
+            add_fraction(&mut res, x.scroll, 1.0 - x.emitted);
+        }
+        res
+    }
+}
+
+fn add_fraction(sum: &mut Option<Scroll>, scroll: Scroll, fraction: f64) {
+    let sum = sum.get_or_insert_default();
+    let add = |sum: &mut Option<f64>, value: Option<f64>| {
+        if let Some(value) = value {
+            *sum = Some(sum.unwrap_or(0.0) + value * fraction);
+        }
+    };
+    add(&mut sum.vertical, scroll.vertical);
+    add(&mut sum.horizontal, scroll.horizontal);
+}
+
+/// Cubic ease-out of the elapsed fraction `t`, clamped to 1.
+fn ease_out(t: f64) -> f64 {
+    1.0 - (1.0 - t.min(1.0)).powi(3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink_event::test_util::{device_config, vertical};
+
+    const CONFIG: &str = "smooth_scroll_ms: 50";
+
+    fn assert_vertical(scroll: Option<Scroll>, v120: f64) {
+        let scroll = scroll.unwrap();
+        assert_eq!(scroll.horizontal, None);
+        assert!((scroll.vertical.unwrap() - v120).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_spread() {
+        let cfg = device_config(CONFIG);
+        let mut state = SmoothScrollState::default();
+
+        state.process(vertical(-120.0), 0);
+        assert_eq!(state.deadline(), Some(10_000));
+        assert_eq!(state.expire(9_999, &cfg), None);
+
+        let mut sum = 0.0;
+        let mut now = 10_000;
+        while let Some(x) = state.deadline() {
+            assert_eq!(x, now);
+            let scroll = state.expire(now, &cfg).unwrap();
+            sum += scroll.vertical.unwrap();
+            now += 10_000;
+        }
+        assert_eq!(now, 60_000);
+        assert!((sum + 120.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_easing() {
+        let cfg = device_config(CONFIG);
+        let mut state = SmoothScrollState::default();
+
+        state.process(vertical(120.0), 0);
+        // 1 - 0.8^3
+        assert_vertical(state.expire(10_000, &cfg), 58.56);
+        // 0.8^3 - 0.6^3
+        assert_vertical(state.expire(20_000, &cfg), 35.52);
+    }
+
+    #[test]
+    fn test_overlap() {
+        let cfg = device_config(CONFIG);
+        let mut state = SmoothScrollState::default();
+
+        state.process(vertical(120.0), 0);
+        state.process(vertical(120.0), 5_000);
+        assert_vertical(state.expire(10_000, &cfg), 58.56);
+        assert_eq!(state.deadline(), Some(15_000));
+        assert_vertical(state.expire(15_000, &cfg), 58.56);
+        assert_eq!(state.deadline(), Some(20_000));
+
+        let mut sum = 0.0;
+        while let Some(x) = state.deadline() {
+            sum += state.expire(x, &cfg).unwrap().vertical.unwrap();
+        }
+        assert!((sum - (240.0 - 58.56 * 2.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_flush() {
+        let cfg = device_config(CONFIG);
+        let mut state = SmoothScrollState::default();
+
+        state.process(vertical(120.0), 0);
+        state.process(vertical(120.0), 5_000);
+        assert_vertical(state.expire(10_000, &cfg), 58.56);
+        assert_vertical(state.flush(), 240.0 - 58.56);
+        assert_eq!(state.deadline(), None);
+        assert_eq!(state.flush(), None);
+    }
+}
@@ -0,0 +1,49 @@
+use evdev::KeyCode;
+use input::event::pointer::ButtonState;
+
+use crate::config::{self, Button};
+
+use super::{ButtonEvent, Scroll};
+
+pub fn button(code: KeyCode) -> Button {
+    Button::from_code(code.code())
+}
+
+pub fn button_event(code: KeyCode, state: ButtonState, time: u64) -> ButtonEvent {
+    ButtonEvent {
+        button: button(code),
+        state,
+        time,
+    }
+}
+
+pub fn scroll(vertical: f64, horizontal: f64) -> Scroll {
+    Scroll {
+        vertical: Some(vertical),
+        horizontal: Some(horizontal),
+    }
+}
+
+pub fn vertical(v120: f64) -> Scroll {
+    Scroll {
+        vertical: Some(v120),
+        horizontal: None,
+    }
+}
+
+pub fn horizontal(v120: f64) -> Scroll {
+    Scroll {
+        vertical: None,
+        horizontal: Some(v120),
+    }
+}
+
+/// Returns the config of a device which is matched by name, with the given options.
+pub fn device_config(options: &str) -> config::Device {
+    let mut cfg: serde_yaml::Mapping = serde_yaml::from_str(options).unwrap();
+    cfg.insert(
+        "match_rule".into(),
+        serde_yaml::from_str("{name: moo}").unwrap(),
+    );
+    serde_yaml::from_value(cfg.into()).unwrap()
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use crate::config::{self, Button, Key, ScrollDirection};
+
+use super::held_modifier::HeldModifier;
+use super::{ButtonEvent, Scroll};
+
+/// Converts mapped wheel directions into key clicks, one for each accumulated notch.
+#[derive(Debug, Default)]
+pub struct WheelMappingState {
+    vertical_v120: f64,
+    horizontal_v120: f64,
+    modifiers: HashMap<Button, HeldModifier>,
+}
+
+impl WheelMappingState {
+    /// Returns the button events to pass on.
+    pub fn process_button(&mut self, ev: ButtonEvent, cfg: &config::Device) -> Vec<ButtonEvent> {
+        if !cfg.is_wheel_modifier(ev.button) {
+            return vec![ev];
+        }
+        self.modifiers.entry(ev.button).or_default().process(ev)
+    }
+
+    /// Returns the scroll of unmapped axes and the keys to click.
+    pub fn process(
+        &mut self,
+        scroll: Scroll,
+        cfg: &config::Device,
+        is_held: impl Fn(Button) -> bool,
+    ) -> (Scroll, Vec<Key>) {
+        if cfg.wheel_mapping.is_empty() {
+            return (scroll, Vec::new());
+        }
+
+        let mut keys = Vec::new();
+        let mut used = Vec::new();
+        let vertical = map_axis(
+            scroll.vertical,
+            &mut self.vertical_v120,
+            (ScrollDirection::Up, ScrollDirection::Down),
+            cfg,
+            &is_held,
+            &mut keys,
+            &mut used,
+        );
+        let horizontal = map_axis(
+            scroll.horizontal,
+            &mut self.horizontal_v120,
+            (ScrollDirection::Left, ScrollDirection::Right),
+            cfg,
+            &is_held,
+            &mut keys,
+            &mut used,
+        );
+        for x in used {
+            if let Some(x) = self.modifiers.get_mut(&x) {
+                x.use_if_held();
+            }
+        }
+        (
+            Scroll {
+                vertical,
+                horizontal,
+            },
+            keys,
+        )
+    }
+}
+
+/// Returns the scroll value if the axis is not mapped, adding the modifier of the mapping to `used`.
+fn map_axis(
+    v120: Option<f64>,
+    accum_v120: &mut f64,
+    (negative, positive): (ScrollDirection, ScrollDirection),
+    cfg: &config::Device,
+    is_held: impl Fn(Button) -> bool,
+    keys: &mut Vec<Key>,
+    used: &mut Vec<Button>,
+) -> Option<f64> {
+    let v120 = v120?;
+    if v120 == 0.0 {
+        return Some(v120);
+    }
+    let direction = if v120 < 0.0 { negative } else { positive };
+    let mapping = match cfg.map_wheel(direction, is_held) {
+        Some(x) => x,
+        None => {
+            *accum_v120 = 0.0;
+            return Some(v120);
+        }
+    };
+
+    used.extend(mapping.modifier);
+
+    // A partial notch in the previous direction does not count toward the new one.
+    if accum_v120.signum() != v120.signum() {
+        *accum_v120 = 0.0;
+    }
+    *accum_v120 += v120;
+    while accum_v120.abs() >= 120.0 {
+        *accum_v120 -= 120.0 * accum_v120.signum();
+        keys.push(mapping.target);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+    use input::event::pointer::ButtonState;
+
+    use super::*;
+    use crate::sink_event::test_util::{button, button_event, device_config, scroll};
+
+    const CONFIG: &str = r#"
+        wheel_mapping:
+          - direction: left
+            target: BTN_BACK
+          - direction: up
+            target: KEY_VOLUMEUP
+            modifier: BTN_SIDE
+        "#;
+
+    fn key(name: &str) -> Key {
+        serde_yaml::from_str(name).unwrap()
+    }
+
+    #[test]
+    fn test_map() {
+        let cfg = device_config(CONFIG);
+        let mut state = WheelMappingState::default();
+
+        let (rest, keys) = state.process(scroll(120.0, -240.0), &cfg, |_| false);
+        assert_eq!(
+            rest,
+            Scroll {
+                horizontal: None,
+                ..scroll(120.0, 0.0)
+            }
+        );
+        assert_eq!(keys, vec![key("BTN_BACK"), key("BTN_BACK")]);
+    }
+
+    #[test]
+    fn test_hi_res_notches() {
+        let cfg = device_config(CONFIG);
+        let mut state = WheelMappingState::default();
+
+        let (_, keys) = state.process(scroll(0.0, -60.0), &cfg, |_| false);
+        assert_eq!(keys, vec![]);
+        let (_, keys) = state.process(scroll(0.0, -90.0), &cfg, |_| false);
+        assert_eq!(keys, vec![key("BTN_BACK")]);
+        let (_, keys) = state.process(scroll(0.0, -90.0), &cfg, |_| false);
+        assert_eq!(keys, vec![key("BTN_BACK")]);
+    }
+
+    #[test]
+    fn test_modifier() {
+        let cfg = device_config(CONFIG);
+        let mut state = WheelMappingState::default();
+
+        let (rest, keys) = state.process(scroll(-120.0, 0.0), &cfg, |_| false);
+        assert_eq!(rest, scroll(-120.0, 0.0));
+        assert_eq!(keys, vec![]);
+
+        let side = button(KeyCode::BTN_SIDE);
+        let (rest, keys) = state.process(scroll(-120.0, 0.0), &cfg, |x| x == side);
+        assert_eq!(
+            rest,
+            Scroll {
+                vertical: None,
+                ..scroll(0.0, 0.0)
+            }
+        );
+        assert_eq!(keys, vec![key("KEY_VOLUMEUP")]);
+    }
+
+    #[test]
+    fn test_modifier_used() {
+        let cfg = device_config(CONFIG);
+        let mut state = WheelMappingState::default();
+        let side = button(KeyCode::BTN_SIDE);
+
+        let press = button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0);
+        assert_eq!(state.process_button(press, &cfg), vec![]);
+        state.process(scroll(-60.0, 0.0), &cfg, |x| x == side);
+        let release = button_event(KeyCode::BTN_SIDE, ButtonState::Released, 0);
+        assert_eq!(state.process_button(release, &cfg), vec![]);
+
+        let other = button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, 0);
+        assert_eq!(state.process_button(other, &cfg), vec![other]);
+    }
+
+    #[test]
+    fn test_modifier_clicked() {
+        let cfg = device_config(CONFIG);
+        let mut state = WheelMappingState::default();
+
+        let press = button_event(KeyCode::BTN_SIDE, ButtonState::Pressed, 0);
+        assert_eq!(state.process_button(press, &cfg), vec![]);
+        // The wheel is not mapped without the modifier held.
+        state.process(scroll(-120.0, 0.0), &cfg, |_| false);
+        let release = button_event(KeyCode::BTN_SIDE, ButtonState::Released, 0);
+        assert_eq!(state.process_button(release, &cfg), vec![press, release]);
+    }
+}
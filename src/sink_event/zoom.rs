@@ -0,0 +1,69 @@
+use input::event::pointer::ButtonState;
+
+use crate::config;
+
+use super::ButtonEvent;
+use super::held_modifier::HeldModifier;
+
+/// Holds Ctrl down for scrolls while the zoom modifier button is held.
+#[derive(Debug, Default)]
+pub struct ZoomState {
+    modifier: HeldModifier,
+    /// True if Ctrl is pressed for scrolls while the modifier is held.
+    ctrl_pressed: bool,
+}
+
+impl ZoomState {
+    /// Returns true if Ctrl should be pressed before a scroll.
+    pub fn begin_scroll(&mut self) -> bool {
+        if self.ctrl_pressed || !self.modifier.use_if_held() {
+            return false;
+        }
+        self.ctrl_pressed = true;
+        true
+    }
+
+    /// Returns the button events to pass on, and true if Ctrl should be released.
+    pub fn process(&mut self, ev: ButtonEvent, cfg: &config::Device) -> (Vec<ButtonEvent>, bool) {
+        if cfg.zoom_modifier != Some(ev.button) {
+            return (vec![ev], false);
+        }
+        let release_ctrl =
+            ev.state == ButtonState::Released && std::mem::take(&mut self.ctrl_pressed);
+        (self.modifier.process(ev), release_ctrl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evdev::KeyCode;
+
+    use super::*;
+    use crate::sink_event::test_util::{button_event, device_config};
+
+    const CONFIG: &str = "zoom_modifier: BTN_EXTRA";
+
+    #[test]
+    fn test_zoom() {
+        let cfg = device_config(CONFIG);
+        let mut state = ZoomState::default();
+
+        assert!(!state.begin_scroll());
+        let press = button_event(KeyCode::BTN_EXTRA, ButtonState::Pressed, 0);
+        assert_eq!(state.process(press, &cfg), (vec![], false));
+        assert!(state.begin_scroll());
+        assert!(!state.begin_scroll());
+        let release = button_event(KeyCode::BTN_EXTRA, ButtonState::Released, 100);
+        assert_eq!(state.process(release, &cfg), (vec![], true));
+        assert!(!state.begin_scroll());
+    }
+
+    #[test]
+    fn test_other_button() {
+        let cfg = device_config(CONFIG);
+        let mut state = ZoomState::default();
+
+        let press = button_event(KeyCode::BTN_LEFT, ButtonState::Pressed, 200);
+        assert_eq!(state.process(press, &cfg), (vec![press], false));
+    }
+}